@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::core::{batch, commentary, error, event, game, rating, squad};
+
+#[derive(Parser)]
+#[command(name = "footsim", about = "simulate football matches")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// play one match
+    Play {
+        #[command(flatten)]
+        args: MatchArgs,
+        /// write commentary of the match, in this tone
+        #[arg(long, value_enum)]
+        commentary: Option<Tone>,
+    },
+    /// play the same match many times and summarise the results
+    Batch {
+        #[command(flatten)]
+        args: MatchArgs,
+        /// number of matches to play
+        #[arg(short = 'n', long, default_value_t = 100)]
+        count: u32,
+        /// threads to play on, all available by default
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+}
+
+#[derive(Args)]
+struct MatchArgs {
+    /// squad file of the home team
+    #[arg(long)]
+    home: PathBuf,
+    /// squad file of the away team
+    #[arg(long)]
+    away: PathBuf,
+    /// seed to replay the same match, batches play seeds counting up from it
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Tone {
+    Neutral,
+    Excitable,
+}
+
+/// final stats of both teams of a match
+#[derive(serde::Serialize)]
+struct MatchResult {
+    home: game::GameStats,
+    away: game::GameStats,
+    ratings: rating::MatchRatings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commentary: Option<commentary::Commentary>,
+}
+
+/// names of both teams and their players to print, ids stand in for missing ones
+struct Names {
+    home: String,
+    away: String,
+    players: HashMap<u32, String>,
+}
+
+impl Cli {
+    pub fn run(self) -> Result<(), Box<dyn Error>> {
+        match self.command {
+            Command::Play { args, commentary } => play(&args, commentary),
+            Command::Batch {
+                args,
+                count,
+                threads,
+            } => batch(&args, count, threads),
+        }
+    }
+}
+
+fn play(args: &MatchArgs, tone: Option<Tone>) -> Result<(), Box<dyn Error>> {
+    let (home, away) = (load_squad(&args.home)?, load_squad(&args.away)?);
+    let names = Names::new(&home, &away);
+    let tone = tone.map(|tone| match tone {
+        Tone::Neutral => commentary::Tone::Neutral,
+        Tone::Excitable => commentary::Tone::Excitable,
+    });
+    let result = play_match(home, away, args.seed, tone, &names)?;
+    match args.format {
+        Format::Text => print_result(&result, &names),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+    }
+    Ok(())
+}
+
+fn batch(args: &MatchArgs, count: u32, threads: Option<usize>) -> Result<(), Box<dyn Error>> {
+    let (home, away) = (load_squad(&args.home)?, load_squad(&args.away)?);
+    let names = Names::new(&home, &away);
+    let mut batch = batch::Batch::new(home, away, count);
+    if let Some(seed) = args.seed {
+        batch.set_seed(seed);
+    }
+    if let Some(threads) = threads {
+        batch.set_threads(threads);
+    }
+    let summary = batch.run()?;
+    match args.format {
+        Format::Text => print_summary(&summary, &names),
+        Format::Json => println!("{}", summary.to_json()?),
+    }
+    Ok(())
+}
+
+/// load a squad from a json file
+fn load_squad(path: &Path) -> Result<squad::Squad, Box<dyn Error>> {
+    let file = std::fs::read_to_string(path)
+        .map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+    let squad = serde_json::from_str(&file)
+        .map_err(|e| format!("can't load squad from {}: {}", path.display(), e))?;
+    Ok(squad)
+}
+
+fn play_match(
+    home: squad::Squad,
+    away: squad::Squad,
+    seed: Option<u64>,
+    tone: Option<commentary::Tone>,
+    names: &Names,
+) -> Result<MatchResult, error::FootsimError> {
+    let mut game = match seed {
+        Some(seed) => game::Game::with_seed(home, away, seed)?,
+        None => game::Game::new(home, away)?,
+    };
+    game.play()?;
+    Ok(MatchResult {
+        home: game.get_home_stats(),
+        away: game.get_away_stats(),
+        ratings: game.get_ratings(),
+        commentary: tone.map(|tone| {
+            let mut commentator =
+                commentary::Commentator::new(commentary::TemplateSet::english(tone));
+            commentator.set_team_names(&names.home, &names.away);
+            commentator.commentate(&game)
+        }),
+    })
+}
+
+fn print_result(result: &MatchResult, names: &Names) {
+    let (home, away) = (&result.home, &result.away);
+    if let Some(commentary) = &result.commentary {
+        for line in commentary.lines.iter() {
+            println!("{}", line);
+        }
+        println!();
+    }
+    println!(
+        "{} {} - {} {}",
+        names.home, home.goals, away.goals, names.away
+    );
+    if !home.shootout.is_empty() {
+        println!(
+            "({} - {} on penalties)",
+            home.get_shootout_goals(),
+            away.get_shootout_goals()
+        );
+    }
+    let rows = [
+        (
+            "possession",
+            format!("{:.0}%", home.possession * 100.0),
+            format!("{:.0}%", away.possession * 100.0),
+        ),
+        ("shots", home.shots.to_string(), away.shots.to_string()),
+        (
+            "on target",
+            home.shots_on_target.to_string(),
+            away.shots_on_target.to_string(),
+        ),
+        ("xG", format!("{:.2}", home.xg), format!("{:.2}", away.xg)),
+        ("passes", home.passes.to_string(), away.passes.to_string()),
+        (
+            "pass accuracy",
+            format!("{:.0}%", get_pass_accuracy(home)),
+            format!("{:.0}%", get_pass_accuracy(away)),
+        ),
+        (
+            "crosses",
+            home.crosses.to_string(),
+            away.crosses.to_string(),
+        ),
+        (
+            "corners",
+            home.corners.to_string(),
+            away.corners.to_string(),
+        ),
+        (
+            "free kicks",
+            home.freekicks.to_string(),
+            away.freekicks.to_string(),
+        ),
+        (
+            "penalties",
+            home.penalties.to_string(),
+            away.penalties.to_string(),
+        ),
+        ("fouls", home.fouls.to_string(), away.fouls.to_string()),
+        (
+            "tackles",
+            home.tackles.to_string(),
+            away.tackles.to_string(),
+        ),
+        (
+            "interceptions",
+            home.interceptions.to_string(),
+            away.interceptions.to_string(),
+        ),
+        ("saves", home.saves.to_string(), away.saves.to_string()),
+        (
+            "distance (km)",
+            format!("{:.1}", home.distance),
+            format!("{:.1}", away.distance),
+        ),
+        (
+            "yellow cards",
+            home.yellow_cards.len().to_string(),
+            away.yellow_cards.len().to_string(),
+        ),
+        (
+            "red cards",
+            home.red_cards.len().to_string(),
+            away.red_cards.len().to_string(),
+        ),
+        (
+            "substitutions",
+            home.substitutions.len().to_string(),
+            away.substitutions.len().to_string(),
+        ),
+        (
+            "injuries",
+            home.injuries.len().to_string(),
+            away.injuries.len().to_string(),
+        ),
+    ];
+    for (name, home, away) in rows {
+        println!("{:<14}{:>6}{:>6}", name, home, away);
+    }
+    for (side, stats) in [(event::Side::Home, home), (event::Side::Away, away)] {
+        for (player_id, goals) in stats.get_goals_by_player() {
+            println!(
+                "{} goal: {} x{}",
+                names.get_team(side),
+                names.get_player(player_id),
+                goals
+            );
+        }
+    }
+    for r in result.ratings.ratings.iter() {
+        println!(
+            "{} rating: {} {:.1}",
+            names.get_team(r.side),
+            names.get_player(r.player_id),
+            r.rating
+        );
+    }
+    if let Some(player_id) = result.ratings.man_of_the_match {
+        println!("man of the match: {}", names.get_player(player_id));
+    }
+    if let Some(commentary) = &result.commentary {
+        println!();
+        println!("{}", commentary.summary);
+    }
+}
+
+/// return share of the passes of a team that found a team mate, in percent
+fn get_pass_accuracy(stats: &game::GameStats) -> f32 {
+    match stats.passes {
+        0 => 0.0,
+        passes => stats.passes_completed as f32 / passes as f32 * 100.0,
+    }
+}
+
+fn print_summary(summary: &batch::BatchSummary, names: &Names) {
+    println!("matches       {:>8}", summary.runs);
+    println!(
+        "outcomes      {:>7.1}% {} win, {:.1}% draw, {:.1}% {} win",
+        summary.home_win * 100.0,
+        names.home,
+        summary.draw * 100.0,
+        summary.away_win * 100.0,
+        names.away
+    );
+    for scoreline in summary.scorelines.iter().take(5) {
+        println!(
+            "scoreline     {:>4} - {:<4}{:.1}%",
+            scoreline.home,
+            scoreline.away,
+            scoreline.probability * 100.0
+        );
+    }
+    let (home, away) = (&summary.home, &summary.away);
+    let rows = [
+        ("goals", home.goals, away.goals),
+        ("possession", home.possession, away.possession),
+        ("shots", home.shots, away.shots),
+        ("on target", home.shots_on_target, away.shots_on_target),
+        ("xG", home.xg, away.xg),
+        ("passes", home.passes, away.passes),
+        ("corners", home.corners, away.corners),
+        ("fouls", home.fouls, away.fouls),
+        ("tackles", home.tackles, away.tackles),
+        ("interceptions", home.interceptions, away.interceptions),
+        ("saves", home.saves, away.saves),
+        ("distance (km)", home.distance, away.distance),
+        ("yellow cards", home.yellow_cards, away.yellow_cards),
+        ("red cards", home.red_cards, away.red_cards),
+    ];
+    println!("{:<14}{:>16}{:>16}", "mean (sd)", names.home, names.away);
+    for (name, home, away) in rows {
+        println!(
+            "{:<14}{:>16}{:>16}",
+            name,
+            format!("{:.2} ({:.2})", home.mean, home.std_dev),
+            format!("{:.2} ({:.2})", away.mean, away.std_dev)
+        );
+    }
+}
+
+impl Names {
+    /// teams without a name go by home and away
+    fn new(home: &squad::Squad, away: &squad::Squad) -> Self {
+        let team = |squad: &squad::Squad, side: &str| match squad.name.is_empty() {
+            true => side.to_string(),
+            false => squad.name.clone(),
+        };
+        let players = [home, away]
+            .into_iter()
+            .flat_map(|squad| squad.players.iter().chain(squad.subs.iter()))
+            .filter(|p| !p.name.is_empty())
+            .map(|p| (p.id, p.name.clone()))
+            .collect();
+        Self {
+            home: team(home, "Home"),
+            away: team(away, "Away"),
+            players,
+        }
+    }
+
+    fn get_team(&self, side: event::Side) -> &str {
+        match side {
+            event::Side::Home => &self.home,
+            event::Side::Away => &self.away,
+        }
+    }
+
+    /// return name of a player, or their id if they have none
+    fn get_player(&self, player_id: u32) -> String {
+        match self.players.get(&player_id) {
+            Some(name) => name.clone(),
+            None => format!("player {}", player_id),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use super::game;
+use super::{error, manager_ai, rules, squad, substitution};
+
+/// plays the same fixture many times to get outcome probabilities and the spread of the stats
+/// every run gets its own seed counting up from the batch seed, so a seeded batch gives the
+/// same summary however many threads it runs on
+pub struct Batch {
+    home: squad::Squad,
+    away: squad::Squad,
+    runs: u32,
+    threads: usize,
+    seed: u64,
+    rules: rules::MatchRules,
+    sub_policy: substitution::SubstitutionPolicy,
+    // `None` to play every run with the tactics the teams started with
+    manager_ai: Option<manager_ai::ManagerAI>,
+}
+
+/// aggregates of a batch
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchSummary {
+    pub runs: u32,
+    // chance of each outcome, by goals at the end of play
+    pub home_win: f32,
+    pub draw: f32,
+    pub away_win: f32,
+    // each (home goals, away goals) scoreline and how often it came up, most common first
+    pub scorelines: Vec<Scoreline>,
+    pub home: TeamSummary,
+    pub away: TeamSummary,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scoreline {
+    pub home: u8,
+    pub away: u8,
+    pub count: u32,
+    pub probability: f32,
+}
+
+/// spread of each stat of a team over the batch, lists are counted by their length
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamSummary {
+    pub possession: Spread,
+    pub crosses: Spread,
+    pub shots: Spread,
+    pub shots_on_target: Spread,
+    pub xg: Spread,
+    // mean is the goals the team scores on average, against `xg` for what its chances were worth
+    pub goals: Spread,
+    pub freekicks: Spread,
+    pub penalties: Spread,
+    pub corners: Spread,
+    pub fouls: Spread,
+    pub tackles: Spread,
+    pub passes: Spread,
+    pub passes_completed: Spread,
+    pub interceptions: Spread,
+    pub saves: Spread,
+    // km covered by everyone who played
+    pub distance: Spread,
+    pub yellow_cards: Spread,
+    pub red_cards: Spread,
+    pub own_goals: Spread,
+    pub substitutions: Spread,
+    pub injuries: Spread,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spread {
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Batch {
+    pub fn new(home_squad: squad::Squad, away_squad: squad::Squad, runs: u32) -> Self {
+        Self {
+            home: home_squad,
+            away: away_squad,
+            runs,
+            threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            seed: rand::random(),
+            rules: rules::MatchRules::default(),
+            sub_policy: substitution::SubstitutionPolicy::default(),
+            manager_ai: None,
+        }
+    }
+
+    /// set number of threads to play the runs on, at least 1
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// set seed of the first run, to get the same summary every time
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn set_rules(&mut self, rules: rules::MatchRules) {
+        self.rules = rules;
+    }
+
+    pub fn set_substitution_policy(&mut self, policy: substitution::SubstitutionPolicy) {
+        self.sub_policy = policy;
+    }
+
+    /// let the managers change tactics during every run, see `Game::set_manager_ai`
+    pub fn set_manager_ai(&mut self, manager_ai: manager_ai::ManagerAI) {
+        self.manager_ai = Some(manager_ai);
+    }
+
+    /// play every run and summarise them
+    /// fails before playing anything if the squads can't be played
+    pub fn run(&self) -> Result<BatchSummary, error::FootsimError> {
+        game::validate(&self.home, &self.away)?;
+        let threads = self.threads.min(self.runs.max(1) as usize);
+        let mut results = Vec::with_capacity(self.runs as usize);
+        std::thread::scope(|scope| {
+            let handles = (0..threads)
+                .map(|thread| {
+                    scope.spawn(move || {
+                        (thread as u32..self.runs)
+                            .step_by(threads)
+                            .map(|run| Ok((run, self.play(run)?)))
+                            .collect::<Result<Vec<_>, error::FootsimError>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                results.extend(
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))?,
+                );
+            }
+            Ok::<_, error::FootsimError>(())
+        })?;
+        // in order of the runs, so the sums don't depend on the threads
+        results.sort_by_key(|(run, _)| *run);
+        let results = results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect::<Vec<_>>();
+        Ok(BatchSummary::from_results(&results))
+    }
+
+    /// play a single run, return stats of (home, away)
+    fn play(&self, run: u32) -> Result<(game::GameStats, game::GameStats), error::FootsimError> {
+        let mut game = game::Game::with_seed(
+            self.home.clone(),
+            self.away.clone(),
+            self.seed.wrapping_add(run as u64),
+        )?;
+        game.set_rules(self.rules.clone());
+        game.set_substitution_policy(self.sub_policy.clone());
+        if let Some(manager_ai) = &self.manager_ai {
+            game.set_manager_ai(manager_ai.clone());
+        }
+        game.play()?;
+        Ok((game.get_home_stats(), game.get_away_stats()))
+    }
+}
+
+impl BatchSummary {
+    /// summarise (home, away) stats of each run
+    pub fn from_results(results: &[(game::GameStats, game::GameStats)]) -> Self {
+        let runs = results.len() as u32;
+        let share = |count: u32| match runs {
+            0 => 0.0,
+            _ => count as f32 / runs as f32,
+        };
+
+        let (mut home_wins, mut draws, mut away_wins) = (0, 0, 0);
+        let mut scorelines: Vec<Scoreline> = Vec::new();
+        for (home, away) in results {
+            match home.goals.cmp(&away.goals) {
+                std::cmp::Ordering::Greater => home_wins += 1,
+                std::cmp::Ordering::Equal => draws += 1,
+                std::cmp::Ordering::Less => away_wins += 1,
+            }
+            match scorelines
+                .iter_mut()
+                .find(|s| s.home == home.goals && s.away == away.goals)
+            {
+                Some(scoreline) => scoreline.count += 1,
+                None => scorelines.push(Scoreline {
+                    home: home.goals,
+                    away: away.goals,
+                    count: 1,
+                    probability: 0.0,
+                }),
+            }
+        }
+        for scoreline in scorelines.iter_mut() {
+            scoreline.probability = share(scoreline.count);
+        }
+        scorelines.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(a.home.cmp(&b.home))
+                .then(a.away.cmp(&b.away))
+        });
+
+        Self {
+            runs,
+            home_win: share(home_wins),
+            draw: share(draws),
+            away_win: share(away_wins),
+            scorelines,
+            home: TeamSummary::from_stats(results.iter().map(|(home, _)| home)),
+            away: TeamSummary::from_stats(results.iter().map(|(_, away)| away)),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl TeamSummary {
+    fn from_stats<'s>(stats: impl Iterator<Item = &'s game::GameStats> + Clone) -> Self {
+        let spread = |value: fn(&game::GameStats) -> f32| {
+            Spread::from_samples(&stats.clone().map(value).collect::<Vec<_>>())
+        };
+        Self {
+            possession: spread(|s| s.possession),
+            crosses: spread(|s| s.crosses as f32),
+            shots: spread(|s| s.shots as f32),
+            shots_on_target: spread(|s| s.shots_on_target as f32),
+            xg: spread(|s| s.xg),
+            goals: spread(|s| s.goals as f32),
+            freekicks: spread(|s| s.freekicks as f32),
+            penalties: spread(|s| s.penalties as f32),
+            corners: spread(|s| s.corners as f32),
+            fouls: spread(|s| s.fouls as f32),
+            tackles: spread(|s| s.tackles as f32),
+            passes: spread(|s| s.passes as f32),
+            passes_completed: spread(|s| s.passes_completed as f32),
+            interceptions: spread(|s| s.interceptions as f32),
+            saves: spread(|s| s.saves as f32),
+            distance: spread(|s| s.distance),
+            yellow_cards: spread(|s| s.yellow_cards.len() as f32),
+            red_cards: spread(|s| s.red_cards.len() as f32),
+            own_goals: spread(|s| s.own_goals.len() as f32),
+            substitutions: spread(|s| s.substitutions.len() as f32),
+            injuries: spread(|s| s.injuries.len() as f32),
+        }
+    }
+}
+
+impl Spread {
+    /// population mean and standard deviation of the samples, all zero for no samples
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|&s| (s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        Self {
+            mean: mean as f32,
+            std_dev: variance.sqrt() as f32,
+            min: samples.iter().copied().fold(f32::INFINITY, f32::min),
+            max: samples.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        }
+    }
+}
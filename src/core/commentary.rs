@@ -0,0 +1,581 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rand::Rng;
+
+use super::{event, game, injury};
+
+/// how worked up the commentator gets
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tone {
+    Neutral,
+    Excitable,
+}
+
+/// something a line of commentary is written for
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Moment {
+    // kick off of the match
+    KickOff,
+    // kick off of any later half
+    Restart,
+    // end of a half, periods of extra time included
+    HalfTime,
+    ExtraTime,
+    FullTime,
+    // goal nobody set up
+    Goal,
+    // `other` is the assister
+    AssistedGoal,
+    // `other` is the crosser
+    HeadedGoal,
+    LongShotGoal,
+    PenaltyGoal,
+    FreeKickGoal,
+    // `player` is the opponent who scored, `team` the team given the goal
+    OwnGoal,
+    // shot off target
+    Miss,
+    // `player` is the keeper, `other` the shooter
+    Save,
+    // `player` is the taker
+    PenaltyAwarded,
+    YellowCard,
+    SecondYellow,
+    RedCard,
+    // injury the player plays on with
+    Knock,
+    // injury that forces the player off
+    Injury,
+    // `player` comes on for `other`
+    Substitution,
+    // manager of `team` changes their tactics
+    TacticsChange,
+    // `player` is the taker, `other` the keeper, `score` the shootout so far
+    ShootoutGoal,
+    ShootoutMiss,
+    // post-match summary, from the final stats
+    Win,
+    Draw,
+    // `score` is the level score of the tie, over both legs for a second leg
+    ShootoutWin,
+    // `winner` goes through on `aggregate` without winning the second leg, `score` of this leg
+    AggregateWin,
+    // `players` is the scorers of `team`
+    Scorers,
+    // possession, shots and xG of both teams
+    Stats,
+    SentOff,
+    ManOfTheMatch,
+}
+
+/// templates of the lines for each moment, in one language and tone
+/// placeholders in braces are filled in from the match:
+/// - lines: {player}, {other}, {team}, {opponent}, {score} as home-away
+/// - summary: {winner}, {loser}, {score} winner first, {shootout}, {aggregate}, {team}, {players},
+///   {player},
+///   and {home}, {away}, {home_possession}, {home_shots}, {home_xg} and the same for away
+///
+/// a moment with no templates gets no line, and a moment with more than one gets a different
+/// one each time it comes up
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemplateSet {
+    pub language: String,
+    pub tone: Tone,
+    // word joining the last two names of a list, e.g. "and"
+    pub and: String,
+    pub templates: HashMap<Moment, Vec<String>>,
+}
+
+/// writes commentary of a match from its events in the language and tone of a template set
+pub struct Commentator {
+    templates: TemplateSet,
+    home_name: String,
+    away_name: String,
+}
+
+/// commentary of a match
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Commentary {
+    // in order of the timeline
+    pub lines: Vec<CommentaryLine>,
+    // post-match summary paragraph
+    pub summary: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommentaryLine {
+    pub minute: u8,
+    // team the line is about, `None` for the whole match
+    pub side: Option<event::Side>,
+    pub moment: Moment,
+    pub text: String,
+}
+
+impl TemplateSet {
+    /// a set with no templates, for every moment to be added with `set_templates`
+    pub fn new(language: &str, tone: Tone, and: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            tone,
+            and: and.to_string(),
+            templates: HashMap::new(),
+        }
+    }
+
+    /// the built in english set
+    pub fn english(tone: Tone) -> Self {
+        use Moment::*;
+        let mut set = Self::new("en", tone, "and");
+        let lines: &[(Moment, &[&str])] = match tone {
+            Tone::Neutral => &[
+                (KickOff, &["{team} get the match under way"]),
+                (Restart, &["{team} get play going again"]),
+                (HalfTime, &["the whistle goes with the score at {score}"]),
+                (ExtraTime, &["level at {score}, so it's extra time"]),
+                (FullTime, &["full time, {score}"]),
+                (Goal, &["goal, {player} scores for {team}", "{player} finds the net for {team}"]),
+                (AssistedGoal, &["goal, {player} finishes after a pass from {other}", "{other} sets up {player} to score"]),
+                (HeadedGoal, &["goal, {player} heads in {other}'s cross"]),
+                (LongShotGoal, &["goal, {player} scores from outside the box"]),
+                (PenaltyGoal, &["{player} scores the penalty"]),
+                (FreeKickGoal, &["{player} scores direct from the free kick"]),
+                (OwnGoal, &["own goal, {player} turns the ball into their own net"]),
+                (Miss, &["{player} shoots wide", "{player} fires over the bar"]),
+                (Save, &["{player} saves from {other}", "{other} is denied by {player}"]),
+                (PenaltyAwarded, &["penalty to {team}"]),
+                (YellowCard, &["{player} is booked"]),
+                (SecondYellow, &["a second yellow for {player}, who is sent off"]),
+                (RedCard, &["{player} is shown a straight red card"]),
+                (Knock, &["{player} takes a knock but carries on"]),
+                (Injury, &["{player} is injured and can't continue"]),
+                (Substitution, &["{team} make a change, {player} comes on for {other}"]),
+                (TacticsChange, &["{team} change their approach"]),
+                (ShootoutGoal, &["{player} scores, {score} in the shootout"]),
+                (ShootoutMiss, &["{player} misses, {score} in the shootout"]),
+                (Win, &["{winner} beat {loser} {score}."]),
+                (Draw, &["{home} and {away} drew {score}."]),
+                (ShootoutWin, &["{winner} beat {loser} {shootout} on penalties after a {score} draw."]),
+                (AggregateWin, &["{winner} go through {aggregate} on aggregate after a {score} second leg against {loser}."]),
+                (Scorers, &["{team} scored through {players}."]),
+                (Stats, &["{home} had {home_possession}% of the ball and {home_shots} shots to {away_shots}, worth {home_xg} xG to {away_xg}."]),
+                (SentOff, &["{player} was sent off for {team}."]),
+                (ManOfTheMatch, &["{player} was named man of the match."]),
+            ],
+            Tone::Excitable => &[
+                (KickOff, &["and we're off! {team} kick us off"]),
+                (Restart, &["back under way, {team} with the ball"]),
+                (HalfTime, &["there's the whistle, {score}"]),
+                (ExtraTime, &["still {score}, we're going to extra time!"]),
+                (FullTime, &["it's all over! {score}"]),
+                (Goal, &["GOAL! {player} scores for {team}!", "GOAL! {player} buries it!"]),
+                (AssistedGoal, &["GOAL! {player} finishes off {other}'s pass!", "GOAL! {other} with the ball, {player} with the finish!"]),
+                (HeadedGoal, &["GOAL! {player} heads in {other}'s cross!"]),
+                (LongShotGoal, &["GOAL! what a strike from {player}, from all of 30 yards!"]),
+                (PenaltyGoal, &["GOAL! {player} sends the keeper the wrong way!"]),
+                (FreeKickGoal, &["GOAL! {player} curls the free kick into the top corner!"]),
+                (OwnGoal, &["oh dear, {player} puts it into their own net!"]),
+                (Miss, &["{player} blazes it over!", "so close from {player}, just wide!"]),
+                (Save, &["what a save from {player} to deny {other}!", "{player} gets down well to keep out {other}"]),
+                (PenaltyAwarded, &["PENALTY to {team}!"]),
+                (YellowCard, &["{player} goes into the book"]),
+                (SecondYellow, &["that's a second yellow for {player}, and {team} are down a man!"]),
+                (RedCard, &["straight red for {player}! {team} are down a man!"]),
+                (Knock, &["{player} is down but gets back up"]),
+                (Injury, &["bad news for {team}, {player} can't go on"]),
+                (Substitution, &["{other} makes way for {player}"]),
+                (TacticsChange, &["the {team} manager is on the touchline barking out new instructions"]),
+                (ShootoutGoal, &["{player} scores! {score}"]),
+                (ShootoutMiss, &["{player} MISSES! {score}"]),
+                (Win, &["{winner} beat {loser} {score}!"]),
+                (Draw, &["nothing to separate {home} and {away}, {score}."]),
+                (ShootoutWin, &["{winner} hold their nerve to beat {loser} {shootout} on penalties after a {score} draw!"]),
+                (AggregateWin, &["{winner} are through, {aggregate} on aggregate, despite a {score} second leg against {loser}!"]),
+                (Scorers, &["{players} on target for {team}."]),
+                (Stats, &["{home} had {home_possession}% of the ball and {home_shots} shots to {away_shots}, worth {home_xg} xG to {away_xg}."]),
+                (SentOff, &["{team} had {player} sent off."]),
+                (ManOfTheMatch, &["{player} was the star of the show."]),
+            ],
+        };
+        for (moment, templates) in lines {
+            set.set_templates(*moment, templates);
+        }
+        set
+    }
+
+    /// replace the templates of a moment, none leaves it out of the commentary
+    pub fn set_templates(&mut self, moment: Moment, templates: &[&str]) {
+        self.templates
+            .insert(moment, templates.iter().map(|t| t.to_string()).collect());
+    }
+
+    /// return a template of the moment, `variant` picks between them
+    pub fn get_template(&self, moment: Moment, variant: usize) -> Option<&str> {
+        let templates = self.templates.get(&moment)?;
+        match templates.len() {
+            0 => None,
+            n => Some(&templates[variant % n]),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl Commentator {
+    /// teams are called "Home" and "Away" until named
+    pub fn new(templates: TemplateSet) -> Self {
+        Self {
+            templates,
+            home_name: "Home".to_string(),
+            away_name: "Away".to_string(),
+        }
+    }
+
+    pub fn set_team_names(&mut self, home: &str, away: &str) {
+        self.home_name = home.to_string();
+        self.away_name = away.to_string();
+    }
+
+    /// write a line for each moment of the match the templates cover, and the summary
+    pub fn commentate<R: Rng>(&self, game: &game::Game<R>) -> Commentary {
+        // players with no name go by their id
+        let name = |id: Option<u32>| match id {
+            Some(id) => match game.get_player(id) {
+                Some(p) if !p.name.is_empty() => p.name.clone(),
+                _ => format!("player {}", id),
+            },
+            None => String::new(),
+        };
+
+        let events = game.get_events();
+        let (mut score, mut shootout) = ((0, 0), (0, 0));
+        let mut lines = Vec::new();
+        for (i, e) in events.iter().enumerate() {
+            match (e.side, &e.kind) {
+                (Some(event::Side::Home), event::EventKind::Goal { .. }) => score.0 += 1,
+                (Some(event::Side::Away), event::EventKind::Goal { .. }) => score.1 += 1,
+                (Some(side), event::EventKind::ShootoutKick { scored: true, .. }) => match side {
+                    event::Side::Home => shootout.0 += 1,
+                    event::Side::Away => shootout.1 += 1,
+                },
+                _ => {}
+            }
+            let (moment, player, other) = match get_moment(events, i) {
+                Some(moment) => moment,
+                None => continue,
+            };
+            let template = match self.templates.get_template(moment, i) {
+                Some(template) => template,
+                None => continue,
+            };
+            let (home_goals, away_goals) = match moment {
+                Moment::ShootoutGoal | Moment::ShootoutMiss => shootout,
+                _ => score,
+            };
+            let (team, opponent) = match e.side {
+                Some(side) => (
+                    self.get_team_name(side),
+                    self.get_team_name(side.opponent()),
+                ),
+                None => ("", ""),
+            };
+            let text = fill(
+                template,
+                &[
+                    ("player", name(player)),
+                    ("other", name(other)),
+                    ("team", team.to_string()),
+                    ("opponent", opponent.to_string()),
+                    ("score", format!("{}-{}", home_goals, away_goals)),
+                ],
+            );
+            lines.push(CommentaryLine {
+                minute: e.minute,
+                side: e.side,
+                moment,
+                text,
+            });
+        }
+        Commentary {
+            lines,
+            summary: self.get_summary(game, &name),
+        }
+    }
+
+    /// return the post-match summary paragraph
+    /// calculated based on:
+    /// - result, on penalties too
+    /// - scorers of each team
+    /// - possession, shots and xG
+    /// - players sent off, man of the match
+    fn get_summary<R: Rng>(
+        &self,
+        game: &game::Game<R>,
+        name: &impl Fn(Option<u32>) -> String,
+    ) -> String {
+        let (home, away) = (game.get_home_stats(), game.get_away_stats());
+        let mut sentences = Vec::new();
+        // the goals pick between templates, so the same match always reads the same
+        let variant = home.goals as usize + away.goals as usize;
+        let mut add = |moment: Moment, values: &[(&str, String)]| {
+            if let Some(template) = self.templates.get_template(moment, variant) {
+                sentences.push(fill(template, values));
+            }
+        };
+
+        // winner first, of the tie rather than this leg, so a second leg can be lost and won
+        let winner_first = |winner: event::Side, (home, away): (u16, u16)| match winner {
+            event::Side::Home => format!("{}-{}", home, away),
+            event::Side::Away => format!("{}-{}", away, home),
+        };
+        let leg = (home.goals as u16, away.goals as u16);
+        let aggregate = game.get_aggregate_score();
+        let shootout = game.get_shootout_score();
+        let score = format!("{}-{}", home.goals, away.goals);
+        match game.get_winner() {
+            Some(winner) => {
+                let won_leg = match winner {
+                    event::Side::Home => home.goals > away.goals,
+                    event::Side::Away => away.goals > home.goals,
+                };
+                let moment = match (shootout, won_leg) {
+                    (Some(_), _) => Moment::ShootoutWin,
+                    (None, true) => Moment::Win,
+                    (None, false) => Moment::AggregateWin,
+                };
+                // a shootout follows a level tie, which is only level over both legs
+                let score = match (moment, aggregate) {
+                    (Moment::ShootoutWin, Some(aggregate)) => winner_first(winner, aggregate),
+                    _ => winner_first(winner, leg),
+                };
+                let shootout = shootout
+                    .map(|(home, away)| winner_first(winner, (home as u16, away as u16)))
+                    .unwrap_or_default();
+                let aggregate = aggregate
+                    .map(|aggregate| winner_first(winner, aggregate))
+                    .unwrap_or_default();
+                add(
+                    moment,
+                    &[
+                        ("winner", self.get_team_name(winner).to_string()),
+                        ("loser", self.get_team_name(winner.opponent()).to_string()),
+                        ("score", score),
+                        ("shootout", shootout),
+                        ("aggregate", aggregate),
+                    ],
+                );
+            }
+            None => add(
+                Moment::Draw,
+                &[
+                    ("home", self.home_name.clone()),
+                    ("away", self.away_name.clone()),
+                    ("score", score),
+                ],
+            ),
+        }
+
+        for (side, stats) in [(event::Side::Home, &home), (event::Side::Away, &away)] {
+            // the opponent who put it in their own net goes on the list of the team given the goal
+            let mut scorers = stats
+                .get_goals_by_player()
+                .into_iter()
+                .map(|(id, goals)| match goals {
+                    1 => name(Some(id)),
+                    goals => format!("{} ({})", name(Some(id)), goals),
+                })
+                .collect::<Vec<_>>();
+            scorers.extend(
+                stats
+                    .own_goals
+                    .iter()
+                    .map(|&id| format!("{} (og)", name(Some(id)))),
+            );
+            if !scorers.is_empty() {
+                add(
+                    Moment::Scorers,
+                    &[
+                        ("team", self.get_team_name(side).to_string()),
+                        ("players", self.join(&scorers)),
+                    ],
+                );
+            }
+        }
+
+        add(
+            Moment::Stats,
+            &[
+                ("home", self.home_name.clone()),
+                ("away", self.away_name.clone()),
+                ("home_possession", format!("{:.0}", home.possession * 100.0)),
+                ("away_possession", format!("{:.0}", away.possession * 100.0)),
+                ("home_shots", home.shots.to_string()),
+                ("away_shots", away.shots.to_string()),
+                ("home_xg", format!("{:.2}", home.xg)),
+                ("away_xg", format!("{:.2}", away.xg)),
+            ],
+        );
+
+        for (side, stats) in [(event::Side::Home, &home), (event::Side::Away, &away)] {
+            for &id in stats.red_cards.iter() {
+                add(
+                    Moment::SentOff,
+                    &[
+                        ("player", name(Some(id))),
+                        ("team", self.get_team_name(side).to_string()),
+                    ],
+                );
+            }
+        }
+
+        if let Some(id) = game.get_ratings().man_of_the_match {
+            add(Moment::ManOfTheMatch, &[("player", name(Some(id)))]);
+        }
+        sentences.join(" ")
+    }
+
+    fn get_team_name(&self, side: event::Side) -> &str {
+        match side {
+            event::Side::Home => &self.home_name,
+            event::Side::Away => &self.away_name,
+        }
+    }
+
+    /// join names into a list, "a, b and c"
+    fn join(&self, names: &[String]) -> String {
+        match names.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} {} {}", rest.join(", "), self.templates.and, last),
+            None => String::new(),
+        }
+    }
+}
+
+impl Commentary {
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for CommentaryLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}' {}", self.minute, self.text)
+    }
+}
+
+/// return the moment of the event at `i` with the (player, other) it's about,
+/// `None` for events that get no commentary
+fn get_moment(
+    events: &[event::MatchEvent],
+    i: usize,
+) -> Option<(Moment, Option<u32>, Option<u32>)> {
+    let e = &events[i];
+    let moment = match e.kind {
+        event::EventKind::KickOff => match e.minute {
+            0 => (Moment::KickOff, None, None),
+            _ => (Moment::Restart, None, None),
+        },
+        event::EventKind::HalfTime | event::EventKind::ExtraTimeHalfTime => {
+            (Moment::HalfTime, None, None)
+        }
+        event::EventKind::ExtraTime => (Moment::ExtraTime, None, None),
+        event::EventKind::FullTime => (Moment::FullTime, None, None),
+        event::EventKind::Goal {
+            player_id,
+            assist_id,
+            goal_type,
+        } => {
+            let moment = match (goal_type, assist_id) {
+                (event::GoalType::OwnGoal, _) => Moment::OwnGoal,
+                (event::GoalType::Penalty, _) => Moment::PenaltyGoal,
+                (event::GoalType::DirectFreeKick, _) => Moment::FreeKickGoal,
+                (event::GoalType::Header, Some(_)) => Moment::HeadedGoal,
+                _ if is_long_shot(events, i, player_id) => Moment::LongShotGoal,
+                (_, Some(_)) => Moment::AssistedGoal,
+                (_, None) => Moment::Goal,
+            };
+            (moment, Some(player_id), assist_id)
+        }
+        event::EventKind::Shot {
+            player_id,
+            on_target: false,
+            ..
+        } => (Moment::Miss, Some(player_id), None),
+        event::EventKind::Save {
+            keeper_id,
+            shooter_id,
+        } => (Moment::Save, Some(keeper_id), Some(shooter_id)),
+        event::EventKind::Penalty { player_id } => (Moment::PenaltyAwarded, Some(player_id), None),
+        event::EventKind::Card {
+            player_id,
+            card,
+            reason,
+        } => {
+            let moment = match (card, reason) {
+                (event::Card::Yellow, _) => Moment::YellowCard,
+                (event::Card::Red, event::CardReason::SecondYellow) => Moment::SecondYellow,
+                (event::Card::Red, _) => Moment::RedCard,
+            };
+            (moment, Some(player_id), None)
+        }
+        event::EventKind::Injury {
+            player_id,
+            severity,
+            ..
+        } => match severity {
+            injury::Severity::Knock => (Moment::Knock, Some(player_id), None),
+            _ => (Moment::Injury, Some(player_id), None),
+        },
+        event::EventKind::Substitution {
+            player_off,
+            player_on,
+            ..
+        } => (Moment::Substitution, Some(player_on), Some(player_off)),
+        event::EventKind::TacticsChange { .. } => (Moment::TacticsChange, None, None),
+        event::EventKind::ShootoutKick {
+            player_id,
+            keeper_id,
+            scored,
+        } => match scored {
+            true => (Moment::ShootoutGoal, Some(player_id), Some(keeper_id)),
+            false => (Moment::ShootoutMiss, Some(player_id), Some(keeper_id)),
+        },
+        _ => return None,
+    };
+    Some(moment)
+}
+
+/// return whether the goal at `i` came from a shot from outside the box
+/// the shot is the last one of the scorer before the goal, in the same minute
+fn is_long_shot(events: &[event::MatchEvent], i: usize, scorer: u32) -> bool {
+    let minute = events[i].minute;
+    events[..i]
+        .iter()
+        .rev()
+        .take_while(|e| e.minute == minute)
+        .find_map(|e| match e.kind {
+            event::EventKind::Shot {
+                player_id, origin, ..
+            } if player_id == scorer => Some(origin == event::ShotOrigin::LongShot),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+/// fill in the placeholders of a template, those with no value are left as they are
+fn fill(template: &str, values: &[(&str, String)]) -> String {
+    let mut text = template.to_string();
+    for (key, value) in values {
+        text = text.replace(&format!("{{{}}}", key), value);
+    }
+    text
+}
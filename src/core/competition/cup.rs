@@ -0,0 +1,289 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::core::{error, event, rules, squad};
+
+use super::{check_entrants, play_match, Fixture};
+
+/// a knockout cup, the winner of each tie goes through to play the winner of the tie next to it
+/// in the bracket until one team is left
+/// with a number of entrants that isn't a power of two, some teams get a bye in the first round
+pub struct Cup {
+    // in order of seeding, first is the top seed
+    teams: Vec<squad::Team>,
+    // rounds drawn so far, the last one is the next to play unless the cup is won
+    rounds: Vec<Round>,
+    draw: Draw,
+    rules: CupRules,
+    seed: u64,
+    // matches played so far
+    matches: u64,
+}
+
+/// how the bracket is drawn
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Draw {
+    // in order of entry, top seeds get the byes and are kept apart until the late rounds
+    Seeded,
+    // teams go into the bracket in a random order
+    Random,
+}
+
+/// how ties are played
+#[derive(Clone, Debug)]
+pub struct CupRules {
+    // ties are played home and away, decided on aggregate
+    pub two_legged: bool,
+    // the final is played over two legs too
+    pub two_legged_final: bool,
+    // goals scored away from home decide a level two legged tie
+    pub away_goals: bool,
+    // play extra time before a penalty shootout when a tie is level
+    pub extra_time: bool,
+}
+
+/// a round of the bracket
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Round {
+    pub ties: Vec<Tie>,
+}
+
+/// two teams drawn against each other, or a team given a bye
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tie {
+    // team drawn first, at home in the first leg
+    pub home: u32,
+    // `None` for a bye, `home` goes through without playing
+    pub away: Option<u32>,
+    // legs played, the second leg has the teams the other way round
+    pub legs: Vec<Fixture>,
+    // score of the shootout of the last leg as (home, away) of the tie, if it came to penalties
+    pub shootout: Option<(u8, u8)>,
+    pub winner: Option<u32>,
+}
+
+impl Default for CupRules {
+    fn default() -> Self {
+        Self {
+            two_legged: false,
+            two_legged_final: false,
+            away_goals: false,
+            extra_time: true,
+        }
+    }
+}
+
+impl Cup {
+    /// `teams` in order of seeding, some get a bye when their number isn't a power of 2
+    /// ties are one leg unless `set_rules` makes them two legged
+    /// fails on the same entrants as `League::new`
+    pub fn new(teams: Vec<squad::Team>) -> Result<Self, error::FootsimError> {
+        check_entrants(&teams, 2)?;
+        Ok(Self {
+            teams,
+            rounds: Vec::new(),
+            draw: Draw::Seeded,
+            rules: CupRules::default(),
+            seed: rand::random(),
+            matches: 0,
+        })
+    }
+
+    pub fn set_draw(&mut self, draw: Draw) {
+        self.draw = draw;
+    }
+
+    pub fn set_rules(&mut self, rules: CupRules) {
+        self.rules = rules;
+    }
+
+    /// set seed of the draw and the matches, to play out the same cup every time
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn get_teams(&self) -> &[squad::Team] {
+        &self.teams
+    }
+
+    /// return rounds drawn so far, with the results of the ties played
+    pub fn get_rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// return number of rounds the cup takes, byes included
+    pub fn get_round_count(&self) -> usize {
+        self.teams.len().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// return id of the team that won the final
+    pub fn get_winner(&self) -> Option<u32> {
+        match self.rounds.last() {
+            Some(round) if round.ties.len() == 1 => round.ties[0].winner,
+            _ => None,
+        }
+    }
+
+    /// draw the first round, if it hasn't been drawn yet
+    /// the first `play_round` makes the draw otherwise
+    pub fn draw(&mut self) {
+        if !self.rounds.is_empty() {
+            return;
+        }
+        let mut ids = self.teams.iter().map(|t| t.id).collect::<Vec<_>>();
+        if self.draw == Draw::Random {
+            ids.shuffle(&mut StdRng::seed_from_u64(self.seed));
+        }
+        let ties = get_bracket_order(ids.len().next_power_of_two())
+            .chunks(2)
+            .map(|pair| Tie::new(ids[pair[0]], ids.get(pair[1]).copied()))
+            .collect();
+        self.rounds.push(Round { ties });
+    }
+
+    /// play the next round and draw the one after from its winners
+    /// return the round played, `None` once the cup is won
+    pub fn play_round(&mut self) -> Result<Option<&Round>, error::FootsimError> {
+        self.draw();
+        if self.get_winner().is_some() {
+            return Ok(None);
+        }
+        let current = self.rounds.len() - 1;
+        let final_round = self.rounds[current].ties.len() == 1;
+        let mut ties = std::mem::take(&mut self.rounds[current].ties);
+        for tie in ties.iter_mut() {
+            self.play_tie(tie, final_round)?;
+        }
+        self.rounds[current].ties = ties;
+
+        if !final_round {
+            let winners = self.rounds[current]
+                .ties
+                .iter()
+                .filter_map(|t| t.winner)
+                .collect::<Vec<_>>();
+            let ties = winners
+                .chunks(2)
+                .map(|pair| Tie::new(pair[0], pair.get(1).copied()))
+                .collect();
+            self.rounds.push(Round { ties });
+        }
+        Ok(self.rounds.get(current))
+    }
+
+    /// play every round left and return id of the winner
+    pub fn play(&mut self) -> Result<Option<u32>, error::FootsimError> {
+        while self.play_round()?.is_some() {}
+        Ok(self.get_winner())
+    }
+
+    /// play the legs of a tie and decide who goes through
+    fn play_tie(&mut self, tie: &mut Tie, final_round: bool) -> Result<(), error::FootsimError> {
+        let away = match tie.away {
+            Some(away) => away,
+            None => {
+                tie.winner = Some(tie.home);
+                return Ok(());
+            }
+        };
+        let two_legged = self.rules.two_legged && (!final_round || self.rules.two_legged_final);
+        let knockout = rules::MatchRules {
+            knockout: true,
+            extra_time: self.rules.extra_time,
+            away_goals: self.rules.away_goals,
+            first_leg: None,
+        };
+        let legs = match two_legged {
+            true => vec![
+                (tie.home, away, rules::MatchRules::default()),
+                (away, tie.home, knockout),
+            ],
+            false => vec![(tie.home, away, knockout)],
+        };
+        for (home, away, mut rules) in legs {
+            // the second leg goes in with the score of the first, from its own home team's side
+            if let Some(first) = tie.legs.first() {
+                rules.first_leg = first.score.map(|(h, a)| (a, h));
+            }
+            let team = |id: u32| self.teams.iter().find(|t| t.id == id);
+            let (home_team, away_team) = match (team(home), team(away)) {
+                (Some(home_team), Some(away_team)) => (home_team, away_team),
+                _ => return Ok(()),
+            };
+            let seed = self.seed.wrapping_add(1 + self.matches);
+            let game = play_match(home_team, away_team, rules, seed)?;
+            self.matches += 1;
+            let (home_goals, away_goals) =
+                (game.get_home_stats().goals, game.get_away_stats().goals);
+            tie.legs.push(Fixture {
+                home,
+                away,
+                score: Some((home_goals, away_goals)),
+            });
+            tie.shootout = game
+                .get_shootout_score()
+                .map(|(h, a)| match home == tie.home {
+                    true => (h, a),
+                    false => (a, h),
+                });
+            tie.winner = game.get_winner().map(|side| match side {
+                event::Side::Home => home,
+                event::Side::Away => away,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Round {
+    /// return name of the round, by the number of teams left in it
+    pub fn get_name(&self) -> String {
+        match self.ties.len() {
+            1 => "final".to_string(),
+            2 => "semi-finals".to_string(),
+            4 => "quarter-finals".to_string(),
+            n => format!("round of {}", n * 2),
+        }
+    }
+}
+
+impl Tie {
+    fn new(home: u32, away: Option<u32>) -> Self {
+        Self {
+            home,
+            away,
+            legs: Vec::new(),
+            shootout: None,
+            winner: None,
+        }
+    }
+
+    /// return goals of (home, away) of the tie over every leg played
+    pub fn get_aggregate(&self) -> (u32, u32) {
+        self.legs
+            .iter()
+            .filter_map(|leg| Some((leg, leg.score?)))
+            .fold((0, 0), |(home, away), (leg, (h, a))| {
+                match leg.home == self.home {
+                    true => (home + h as u32, away + a as u32),
+                    false => (home + a as u32, away + h as u32),
+                }
+            })
+    }
+}
+
+/// return seeds (0 = top seed) in bracket order for a bracket of `size` teams, a power of two
+/// each pair plays in the first round, and the top two seeds can only meet in the final
+fn get_bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let n = order.len() * 2;
+        order = order
+            .iter()
+            .flat_map(|&seed| [seed, n - 1 - seed])
+            .collect();
+    }
+    order
+}
@@ -0,0 +1,350 @@
+use crate::core::{error, rules, squad};
+
+use super::{check_entrants, play_match, Fixture};
+
+const WIN_POINTS: u32 = 3;
+const DRAW_POINTS: u32 = 1;
+
+/// a round robin league, every team plays every other team home and away unless set to play
+/// each other once
+/// teams are ranked by points, then by the tiebreakers in order, then by order of entry
+pub struct League {
+    teams: Vec<squad::Team>,
+    // fixtures of each matchday, a team plays at most once a matchday
+    matchdays: Vec<Vec<Fixture>>,
+    // matchdays played so far
+    played: usize,
+    tiebreakers: Vec<Tiebreaker>,
+    seed: u64,
+}
+
+/// how teams level on points are separated
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tiebreaker {
+    // points, then goal difference, then goals scored in the matches between the teams level
+    HeadToHead,
+    GoalDifference,
+    GoalsScored,
+}
+
+/// league table, in order of position
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    // matchdays played
+    pub matchday: usize,
+    pub standings: Vec<Standing>,
+}
+
+/// row of a league table
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standing {
+    // 1 for the top of the table
+    pub position: usize,
+    pub team_id: u32,
+    pub name: String,
+    pub played: u32,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    pub goals_for: u32,
+    pub goals_against: u32,
+    pub goal_difference: i32,
+    pub points: u32,
+}
+
+impl League {
+    /// every team plays every other home and away
+    /// fails with fewer than 2 teams, a team id entered twice, or a manager or player whose
+    /// `team_id` isn't the team they're entered with
+    pub fn new(teams: Vec<squad::Team>) -> Result<Self, error::FootsimError> {
+        check_entrants(&teams, 2)?;
+        let ids = teams.iter().map(|t| t.id).collect::<Vec<_>>();
+        Ok(Self {
+            teams,
+            matchdays: get_round_robin(&ids),
+            played: 0,
+            tiebreakers: vec![
+                Tiebreaker::GoalDifference,
+                Tiebreaker::GoalsScored,
+                Tiebreaker::HeadToHead,
+            ],
+            seed: rand::random(),
+        })
+    }
+
+    /// `true` to play every other team home and away, as a new league does, `false` to play them
+    /// once, ignored once a matchday is played
+    pub fn set_double_round_robin(&mut self, double: bool) {
+        if self.played > 0 {
+            return;
+        }
+        let ids = self.teams.iter().map(|t| t.id).collect::<Vec<_>>();
+        self.matchdays = get_round_robin(&ids);
+        if !double {
+            self.matchdays.truncate(self.matchdays.len() / 2);
+        }
+    }
+
+    /// set tiebreakers to separate teams level on points, in the order they're applied
+    pub fn set_tiebreakers(&mut self, tiebreakers: Vec<Tiebreaker>) {
+        self.tiebreakers = tiebreakers;
+    }
+
+    /// set seed of the first match, to play out the same league every time
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn get_teams(&self) -> &[squad::Team] {
+        &self.teams
+    }
+
+    /// return fixtures of every matchday, with the scores of those played
+    pub fn get_matchdays(&self) -> &[Vec<Fixture>] {
+        &self.matchdays
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.played == self.matchdays.len()
+    }
+
+    /// play the next matchday and return the table after it, `None` once every matchday is played
+    pub fn play_matchday(&mut self) -> Result<Option<Table>, error::FootsimError> {
+        if self.is_finished() {
+            return Ok(None);
+        }
+        let first_match = self.matchdays[..self.played]
+            .iter()
+            .map(|m| m.len())
+            .sum::<usize>();
+        for (i, fixture) in self.matchdays[self.played].iter_mut().enumerate() {
+            let team = |id: u32| self.teams.iter().find(|t| t.id == id);
+            let (home, away) = match (team(fixture.home), team(fixture.away)) {
+                (Some(home), Some(away)) => (home, away),
+                _ => continue,
+            };
+            let seed = self.seed.wrapping_add((first_match + i) as u64);
+            let game = play_match(home, away, rules::MatchRules::default(), seed)?;
+            fixture.score = Some((game.get_home_stats().goals, game.get_away_stats().goals));
+        }
+        self.played += 1;
+        Ok(Some(self.get_table()))
+    }
+
+    /// play every matchday left and return the final table
+    pub fn play(&mut self) -> Result<Table, error::FootsimError> {
+        while self.play_matchday()?.is_some() {}
+        Ok(self.get_table())
+    }
+
+    /// return the table after the matchdays played so far
+    pub fn get_table(&self) -> Table {
+        Table::from_fixtures(
+            self.played,
+            &self.teams,
+            self.matchdays.iter().flatten(),
+            &self.tiebreakers,
+        )
+    }
+
+    /// give back the teams, e.g. to enter them into the next season
+    pub fn into_teams(self) -> Vec<squad::Team> {
+        self.teams
+    }
+}
+
+impl Table {
+    /// build the table of the teams from the fixtures played between them
+    /// fixtures not played, or with a team not in `teams`, are left out
+    pub fn from_fixtures<'f>(
+        matchday: usize,
+        teams: &[squad::Team],
+        fixtures: impl Iterator<Item = &'f Fixture> + Clone,
+        tiebreakers: &[Tiebreaker],
+    ) -> Self {
+        let mut rows = teams
+            .iter()
+            .map(|t| Standing::new(t.id, t.name.clone()))
+            .collect::<Vec<_>>();
+        let played = fixtures
+            .filter(|f| f.score.is_some())
+            .filter(|f| teams.iter().any(|t| t.id == f.home))
+            .filter(|f| teams.iter().any(|t| t.id == f.away));
+        for fixture in played.clone() {
+            let (home_goals, away_goals) = fixture.score.unwrap_or_default();
+            for row in rows.iter_mut() {
+                if row.team_id == fixture.home {
+                    row.add_result(home_goals, away_goals);
+                } else if row.team_id == fixture.away {
+                    row.add_result(away_goals, home_goals);
+                }
+            }
+        }
+
+        let mut order = (0..rows.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| std::cmp::Reverse(rows[i].points));
+        let fixtures = played.collect::<Vec<_>>();
+        let ranked = split_by(&order, |&i| rows[i].points)
+            .into_iter()
+            .flat_map(|group| break_ties(&rows, group, &fixtures, tiebreakers))
+            .collect::<Vec<_>>();
+        let standings = ranked
+            .into_iter()
+            .enumerate()
+            .map(|(position, i)| Standing {
+                position: position + 1,
+                ..rows[i].clone()
+            })
+            .collect();
+        Self {
+            matchday,
+            standings,
+        }
+    }
+
+    pub fn get_standing(&self, team_id: u32) -> Option<&Standing> {
+        self.standings.iter().find(|s| s.team_id == team_id)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl Standing {
+    fn new(team_id: u32, name: String) -> Self {
+        Self {
+            position: 0,
+            team_id,
+            name,
+            played: 0,
+            won: 0,
+            drawn: 0,
+            lost: 0,
+            goals_for: 0,
+            goals_against: 0,
+            goal_difference: 0,
+            points: 0,
+        }
+    }
+
+    fn add_result(&mut self, scored: u8, conceded: u8) {
+        self.played += 1;
+        self.goals_for += scored as u32;
+        self.goals_against += conceded as u32;
+        self.goal_difference = self.goals_for as i32 - self.goals_against as i32;
+        match scored.cmp(&conceded) {
+            std::cmp::Ordering::Greater => {
+                self.won += 1;
+                self.points += WIN_POINTS;
+            }
+            std::cmp::Ordering::Equal => {
+                self.drawn += 1;
+                self.points += DRAW_POINTS;
+            }
+            std::cmp::Ordering::Less => self.lost += 1,
+        }
+    }
+}
+
+/// return fixtures of a double round robin by the circle method, one list per matchday
+/// with an odd number of teams one team sits out each matchday
+/// the second half of the season replays the first with home and away swapped
+fn get_round_robin(ids: &[u32]) -> Vec<Vec<Fixture>> {
+    let mut slots = ids.iter().map(|&id| Some(id)).collect::<Vec<_>>();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let n = slots.len();
+    let mut matchdays = Vec::new();
+    for round in 0..n.saturating_sub(1) {
+        let mut fixtures = Vec::new();
+        for i in 0..n / 2 {
+            if let (Some(a), Some(b)) = (slots[i], slots[n - 1 - i]) {
+                // swap sides every other round so no team is stuck at home or away
+                let home_first = match i {
+                    0 => round % 2 == 0,
+                    _ => i % 2 == 1,
+                };
+                fixtures.push(match home_first {
+                    true => Fixture::new(a, b),
+                    false => Fixture::new(b, a),
+                });
+            }
+        }
+        matchdays.push(fixtures);
+        // the first team stays put, everyone else moves round one place
+        slots[1..].rotate_right(1);
+    }
+    let second_half = matchdays
+        .iter()
+        .map(|m| m.iter().map(|f| Fixture::new(f.away, f.home)).collect())
+        .collect::<Vec<_>>();
+    matchdays.extend(second_half);
+    matchdays
+}
+
+/// order a group of teams level on points by the tiebreakers, in order of entry if still level
+/// `group` and the result are indexes into `rows`
+fn break_ties(
+    rows: &[Standing],
+    mut group: Vec<usize>,
+    fixtures: &[&Fixture],
+    tiebreakers: &[Tiebreaker],
+) -> Vec<usize> {
+    let (tiebreaker, rest) = match tiebreakers.split_first() {
+        Some(split) if group.len() > 1 => split,
+        _ => return group,
+    };
+    let key = |i: usize| match tiebreaker {
+        Tiebreaker::HeadToHead => get_head_to_head(rows, &group, fixtures, rows[i].team_id),
+        Tiebreaker::GoalDifference => (rows[i].goal_difference, 0, 0),
+        Tiebreaker::GoalsScored => (rows[i].goals_for as i32, 0, 0),
+    };
+    let keys = group.iter().map(|&i| (i, key(i))).collect::<Vec<_>>();
+    let key_of = |i: usize| keys.iter().find(|(j, _)| *j == i).map(|(_, k)| *k);
+    group.sort_by_key(|&i| std::cmp::Reverse(key_of(i)));
+    split_by(&group, |&i| key_of(i))
+        .into_iter()
+        .flat_map(|level| break_ties(rows, level, fixtures, rest))
+        .collect()
+}
+
+/// return (points, goal difference, goals scored) of a team in the matches between the group
+fn get_head_to_head(
+    rows: &[Standing],
+    group: &[usize],
+    fixtures: &[&Fixture],
+    team_id: u32,
+) -> (i32, i32, i32) {
+    let in_group = |id: u32| group.iter().any(|&i| rows[i].team_id == id);
+    let mut row = Standing::new(team_id, String::new());
+    for fixture in fixtures
+        .iter()
+        .filter(|f| in_group(f.home) && in_group(f.away))
+    {
+        let (home_goals, away_goals) = fixture.score.unwrap_or_default();
+        if fixture.home == team_id {
+            row.add_result(home_goals, away_goals);
+        } else if fixture.away == team_id {
+            row.add_result(away_goals, home_goals);
+        }
+    }
+    (row.points as i32, row.goal_difference, row.goals_for as i32)
+}
+
+/// split sorted items into runs with the same key
+fn split_by<T: Copy, K: PartialEq>(items: &[T], key: impl Fn(&T) -> K) -> Vec<Vec<T>> {
+    let mut runs: Vec<Vec<T>> = Vec::new();
+    for item in items {
+        match runs.last_mut() {
+            Some(run) if key(&run[0]) == key(item) => run.push(*item),
+            _ => runs.push(vec![*item]),
+        }
+    }
+    runs
+}
@@ -0,0 +1,68 @@
+use super::{error, game, rules, squad};
+
+mod cup;
+mod league;
+pub mod pyramid;
+pub mod tournament;
+
+pub use cup::{Cup, CupRules, Draw, Round};
+pub use league::{League, Table, Tiebreaker};
+
+/// a match between two teams of a competition, by team id
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixture {
+    pub home: u32,
+    pub away: u32,
+    // goals of (home, away), `None` until played
+    pub score: Option<(u8, u8)>,
+}
+
+impl Fixture {
+    pub fn new(home: u32, away: u32) -> Self {
+        Self {
+            home,
+            away,
+            score: None,
+        }
+    }
+}
+
+/// check there are at least `needed` teams, none is entered twice and the players and manager
+/// of each belong to it
+fn check_entrants(teams: &[squad::Team], needed: usize) -> Result<(), error::FootsimError> {
+    if teams.len() < needed {
+        return Err(error::FootsimError::NotEnoughTeams(teams.len(), needed));
+    }
+    for (i, team) in teams.iter().enumerate() {
+        if teams[..i].iter().any(|t| t.id == team.id) {
+            return Err(error::FootsimError::DuplicateTeam(team.id));
+        }
+        if team.manager.team_id != team.id {
+            return Err(error::FootsimError::ManagerNotInTeam {
+                team_id: team.id,
+                manager_id: team.manager.id,
+            });
+        }
+        if let Some(player) = team.players.iter().find(|p| p.team_id != team.id) {
+            return Err(error::FootsimError::PlayerNotInTeam {
+                team_id: team.id,
+                player_id: player.id,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// play a match between the squads the teams pick for it
+fn play_match(
+    home: &squad::Team,
+    away: &squad::Team,
+    rules: rules::MatchRules,
+    seed: u64,
+) -> Result<game::Game, error::FootsimError> {
+    let mut game = game::Game::with_seed(home.pick_squad()?, away.pick_squad()?, seed)?;
+    game.set_rules(rules);
+    game.play()?;
+    Ok(game)
+}
@@ -0,0 +1,230 @@
+use crate::core::{error, squad};
+
+use super::{check_entrants, Cup, Draw, League, Round, Table, Tiebreaker};
+
+/// divisions linked by promotion and relegation, played a season at a time
+/// at the end of each season the top teams of a division swap places with the bottom teams of
+/// the division above, and the teams carry over into the next season with the same ids
+pub struct Pyramid {
+    // from the top division down
+    divisions: Vec<Division>,
+    // seasons played so far
+    season: u32,
+    // tiebreakers of every division, those of the league if not set
+    tiebreakers: Option<Vec<Tiebreaker>>,
+    seed: u64,
+}
+
+/// a division of the pyramid, played as a double round robin league
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Division {
+    pub name: String,
+    // in order of entry, after a season the teams relegated from above come first, then the
+    // teams that stayed in order of the table, then the teams promoted from below
+    pub teams: Vec<squad::Team>,
+    // teams going up to the division above at the end of the season, ignored for the top one
+    pub promoted: usize,
+    // places below the promoted ones that play off in a cup for one more place going up,
+    // no playoff with fewer than 2
+    pub playoff_places: usize,
+}
+
+/// results of a season, with the teams moving between divisions
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeasonReport {
+    // 1 for the first season
+    pub season: u32,
+    pub divisions: Vec<DivisionReport>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DivisionReport {
+    pub name: String,
+    pub table: Table,
+    // teams going up, the playoff winner last
+    pub promoted: Vec<u32>,
+    // teams going down, in order of the table
+    pub relegated: Vec<u32>,
+    // rounds of the promotion playoff, empty without one
+    pub playoff: Vec<Round>,
+}
+
+impl Pyramid {
+    /// `divisions` from the top down
+    /// fails if a division has fewer than 2 teams or too few to move the teams going up and
+    /// down, if a team is entered twice, or if a player or manager belongs to another team
+    pub fn new(divisions: Vec<Division>) -> Result<Self, error::FootsimError> {
+        let teams = divisions
+            .iter()
+            .flat_map(|d| d.teams.iter().cloned())
+            .collect::<Vec<_>>();
+        check_entrants(&teams, 2)?;
+        for (d, division) in divisions.iter().enumerate() {
+            check_entrants(&division.teams, 2)?;
+            let up = match d {
+                0 => 0,
+                _ => division.promoted + division.get_playoff_places(),
+            };
+            let down = divisions.get(d + 1).map_or(0, |below| below.get_going_up());
+            if division.teams.len() < up + down {
+                return Err(error::FootsimError::NotEnoughTeams(
+                    division.teams.len(),
+                    up + down,
+                ));
+            }
+        }
+        Ok(Self {
+            divisions,
+            season: 0,
+            tiebreakers: None,
+            seed: rand::random(),
+        })
+    }
+
+    pub fn set_tiebreakers(&mut self, tiebreakers: Vec<Tiebreaker>) {
+        self.tiebreakers = Some(tiebreakers);
+    }
+
+    /// set seed of the first season, to play out the same seasons every time
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// return divisions from the top down, with the teams for the next season
+    pub fn get_divisions(&self) -> &[Division] {
+        &self.divisions
+    }
+
+    /// return number of seasons played
+    pub fn get_season(&self) -> u32 {
+        self.season
+    }
+
+    /// play every division and the playoffs, then move teams up and down for the next season
+    pub fn play_season(&mut self) -> Result<SeasonReport, error::FootsimError> {
+        let seed = self.seed.wrapping_add((self.season as u64) << 40);
+        let mut reports = Vec::new();
+        for (d, division) in self.divisions.iter().enumerate() {
+            let mut league = League::new(division.teams.clone())?;
+            if let Some(tiebreakers) = &self.tiebreakers {
+                league.set_tiebreakers(tiebreakers.clone());
+            }
+            league.set_seed(seed.wrapping_add((d as u64) << 32));
+            let table = league.play()?;
+
+            let mut promoted = Vec::new();
+            let mut playoff = Vec::new();
+            if d > 0 {
+                promoted.extend(
+                    table.standings[..division.promoted]
+                        .iter()
+                        .map(|s| s.team_id),
+                );
+                let places = division.get_playoff_places();
+                if places > 0 {
+                    let teams = table.standings[division.promoted..division.promoted + places]
+                        .iter()
+                        .filter_map(|s| division.get_team(s.team_id).cloned())
+                        .collect();
+                    // higher placed teams are the top seeds
+                    let mut cup = Cup::new(teams)?;
+                    cup.set_draw(Draw::Seeded);
+                    cup.set_seed(seed.wrapping_add(((d as u64) << 32) + (1 << 31)));
+                    promoted.extend(cup.play()?);
+                    playoff = cup.get_rounds().to_vec();
+                }
+            }
+            let down = self
+                .divisions
+                .get(d + 1)
+                .map_or(0, |below| below.get_going_up());
+            let relegated = table.standings[table.standings.len() - down..]
+                .iter()
+                .map(|s| s.team_id)
+                .collect();
+            reports.push(DivisionReport {
+                name: division.name.clone(),
+                table,
+                promoted,
+                relegated,
+                playoff,
+            });
+        }
+
+        let mut next = Vec::new();
+        for (d, report) in reports.iter().enumerate() {
+            let mut ids = match d {
+                0 => Vec::new(),
+                _ => reports[d - 1].relegated.clone(),
+            };
+            ids.extend(
+                report
+                    .table
+                    .standings
+                    .iter()
+                    .map(|s| s.team_id)
+                    .filter(|id| !report.promoted.contains(id) && !report.relegated.contains(id)),
+            );
+            if let Some(below) = reports.get(d + 1) {
+                ids.extend(&below.promoted);
+            }
+            next.push(ids);
+        }
+        let teams = self
+            .divisions
+            .iter()
+            .flat_map(|d| d.teams.iter().cloned())
+            .collect::<Vec<_>>();
+        for (division, ids) in self.divisions.iter_mut().zip(next) {
+            division.teams = ids
+                .iter()
+                .filter_map(|&id| teams.iter().find(|t| t.id == id).cloned())
+                .collect();
+        }
+
+        self.season += 1;
+        Ok(SeasonReport {
+            season: self.season,
+            divisions: reports,
+        })
+    }
+}
+
+impl Division {
+    /// a division with no teams going up
+    pub fn new(name: &str, teams: Vec<squad::Team>) -> Self {
+        Self {
+            name: name.to_string(),
+            teams,
+            promoted: 0,
+            playoff_places: 0,
+        }
+    }
+
+    pub fn get_team(&self, team_id: u32) -> Option<&squad::Team> {
+        self.teams.iter().find(|t| t.id == team_id)
+    }
+
+    /// return places in the playoff, 0 without one
+    fn get_playoff_places(&self) -> usize {
+        match self.playoff_places {
+            0 | 1 => 0,
+            places => places,
+        }
+    }
+
+    /// return number of teams going up, the playoff winner included
+    fn get_going_up(&self) -> usize {
+        self.promoted + self.get_playoff_places().min(1)
+    }
+}
+
+impl SeasonReport {
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,364 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::core::{error, squad};
+
+use super::{Cup, CupRules, Draw, Fixture, League, Round, Table, Tiebreaker};
+
+/// groups of round robin leagues, with the top teams of each going through to a knockout cup
+/// teams are entered in order of seeding, which also makes the pots: the first pot holds the
+/// top seeds, one for each group, then the next pot the next seeds and so on
+pub struct Tournament {
+    teams: Vec<squad::Team>,
+    group_count: usize,
+    // teams going through from each group
+    qualifiers: usize,
+    // teams going through on top from the best of those placed just below the qualifiers
+    best_placed: usize,
+    // draw one team from each pot into every group, otherwise teams are drawn at random
+    pots: bool,
+    // keep teams of the same country in different groups
+    separate_countries: bool,
+    // groups play home and away
+    double_round_robin: bool,
+    // tiebreakers of the groups, those of the league if not set
+    tiebreakers: Option<Vec<Tiebreaker>>,
+    cup_rules: CupRules,
+    seed: u64,
+    // empty until drawn
+    groups: Vec<League>,
+    // `None` until the group stage is over
+    knockout: Option<Cup>,
+}
+
+/// results of a tournament so far
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TournamentReport {
+    pub groups: Vec<GroupReport>,
+    // teams through to the knockout, in order of seeding
+    pub qualified: Vec<u32>,
+    pub knockout: Vec<Round>,
+    pub winner: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupReport {
+    pub name: String,
+    pub fixtures: Vec<Fixture>,
+    pub table: Table,
+}
+
+impl Tournament {
+    /// `teams` in order of seeding, split into `groups` groups of at least 2 teams
+    /// fails with too few teams for the groups or a team entered twice
+    pub fn new(teams: Vec<squad::Team>, groups: usize) -> Result<Self, error::FootsimError> {
+        let group_count = groups.max(1);
+        super::check_entrants(&teams, group_count * 2)?;
+        Ok(Self {
+            teams,
+            group_count,
+            qualifiers: 2,
+            best_placed: 0,
+            pots: true,
+            separate_countries: false,
+            double_round_robin: false,
+            tiebreakers: None,
+            cup_rules: CupRules::default(),
+            seed: rand::random(),
+            groups: Vec::new(),
+            knockout: None,
+        })
+    }
+
+    /// set number of teams going through from each group, at least 1
+    pub fn set_qualifiers(&mut self, qualifiers: usize) {
+        self.qualifiers = qualifiers.max(1);
+    }
+
+    /// set number of teams going through from the best of those placed just below the
+    /// qualifiers, e.g. the best third placed teams, at most one from each group
+    pub fn set_best_placed(&mut self, best_placed: usize) {
+        self.best_placed = best_placed;
+    }
+
+    /// draw from pots by seeding, or every team at random
+    pub fn set_pots(&mut self, pots: bool) {
+        self.pots = pots;
+    }
+
+    /// keep teams of the same country in different groups, by `squad::Team::get_country_id`
+    pub fn set_separate_countries(&mut self, separate: bool) {
+        self.separate_countries = separate;
+    }
+
+    /// groups play home and away rather than once
+    pub fn set_double_round_robin(&mut self, double: bool) {
+        self.double_round_robin = double;
+    }
+
+    pub fn set_tiebreakers(&mut self, tiebreakers: Vec<Tiebreaker>) {
+        self.tiebreakers = Some(tiebreakers);
+    }
+
+    /// set rules of the knockout ties
+    pub fn set_cup_rules(&mut self, rules: CupRules) {
+        self.cup_rules = rules;
+    }
+
+    /// set seed of the draw and the matches, to play out the same tournament every time
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn get_teams(&self) -> &[squad::Team] {
+        &self.teams
+    }
+
+    /// return the groups drawn, empty until the draw
+    pub fn get_groups(&self) -> &[League] {
+        &self.groups
+    }
+
+    /// return the knockout cup, `None` until the group stage is over
+    pub fn get_knockout(&self) -> Option<&Cup> {
+        self.knockout.as_ref()
+    }
+
+    /// draw the groups, if they haven't been drawn yet
+    /// the group stage makes the draw otherwise
+    /// fails if no draw keeps teams of the same country apart
+    pub fn draw(&mut self) -> Result<(), error::FootsimError> {
+        if !self.groups.is_empty() {
+            return Ok(());
+        }
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut order = (0..self.teams.len()).collect::<Vec<_>>();
+        if !self.pots {
+            order.shuffle(&mut rng);
+        }
+        // team index, pot and country of each team in the order they come out of the pots
+        let mut drawn = Vec::new();
+        for (pot, teams) in order.chunks(self.group_count).enumerate() {
+            let mut teams = teams.to_vec();
+            teams.shuffle(&mut rng);
+            for i in teams {
+                let country = match self.separate_countries {
+                    true => Some(self.teams[i].get_country_id()),
+                    false => None,
+                };
+                drawn.push((i, pot, country));
+            }
+        }
+        // no draw can keep more teams of a country apart than there are groups, with no more
+        // than that there always is one
+        for &(_, _, country) in &drawn {
+            let same = drawn
+                .iter()
+                .filter(|(_, _, c)| c.is_some() && *c == country);
+            if same.count() > self.group_count {
+                return Err(error::FootsimError::NoValidDraw);
+            }
+        }
+        let groups =
+            place_in_groups(&drawn, self.group_count).ok_or(error::FootsimError::NoValidDraw)?;
+
+        for (g, group) in groups.iter().enumerate() {
+            let teams = group
+                .iter()
+                .map(|&d| self.teams[drawn[d].0].clone())
+                .collect();
+            let mut league = League::new(teams)?;
+            league.set_double_round_robin(self.double_round_robin);
+            if let Some(tiebreakers) = &self.tiebreakers {
+                league.set_tiebreakers(tiebreakers.clone());
+            }
+            league.set_seed(self.seed.wrapping_add((g as u64 + 1) << 32));
+            self.groups.push(league);
+        }
+        Ok(())
+    }
+
+    /// play every group and return the final tables
+    pub fn play_group_stage(&mut self) -> Result<Vec<Table>, error::FootsimError> {
+        self.draw()?;
+        self.groups.iter_mut().map(|group| group.play()).collect()
+    }
+
+    /// return teams through to the knockout in order of seeding, empty until the group stage
+    /// is over
+    /// group winners go first, in order of the groups, then the runners up and so on, then the
+    /// best placed teams below the qualifiers by points, goal difference and goals scored
+    pub fn get_qualified(&self) -> Vec<u32> {
+        if self.groups.is_empty() || !self.groups.iter().all(|g| g.is_finished()) {
+            return Vec::new();
+        }
+        let tables = self
+            .groups
+            .iter()
+            .map(|g| g.get_table())
+            .collect::<Vec<_>>();
+        let mut qualified = Vec::new();
+        for place in 0..self.qualifiers {
+            for table in &tables {
+                if let Some(standing) = table.standings.get(place) {
+                    qualified.push(standing.team_id);
+                }
+            }
+        }
+        let mut next = tables
+            .iter()
+            .filter_map(|t| t.standings.get(self.qualifiers))
+            .collect::<Vec<_>>();
+        next.sort_by_key(|s| std::cmp::Reverse((s.points, s.goal_difference, s.goals_for)));
+        qualified.extend(next.iter().take(self.best_placed).map(|s| s.team_id));
+        qualified
+    }
+
+    /// play the group stage and the knockout and return the report of the tournament
+    pub fn play(&mut self) -> Result<TournamentReport, error::FootsimError> {
+        self.play_group_stage()?;
+        if self.knockout.is_none() {
+            let qualified = self
+                .get_qualified()
+                .into_iter()
+                .filter_map(|id| self.teams.iter().find(|t| t.id == id).cloned())
+                .collect();
+            let mut cup = Cup::new(qualified)?;
+            cup.set_draw(Draw::Seeded);
+            cup.set_rules(self.cup_rules.clone());
+            cup.set_seed(self.seed.wrapping_add(1 << 63));
+            self.knockout = Some(cup);
+        }
+        if let Some(cup) = self.knockout.as_mut() {
+            cup.play()?;
+        }
+        Ok(self.get_report())
+    }
+
+    /// return the results so far
+    pub fn get_report(&self) -> TournamentReport {
+        let groups = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(g, group)| GroupReport {
+                name: get_group_name(g),
+                fixtures: group.get_matchdays().iter().flatten().cloned().collect(),
+                table: group.get_table(),
+            })
+            .collect();
+        TournamentReport {
+            groups,
+            qualified: self.get_qualified(),
+            knockout: self
+                .knockout
+                .as_ref()
+                .map(|cup| cup.get_rounds().to_vec())
+                .unwrap_or_default(),
+            winner: self.knockout.as_ref().and_then(|cup| cup.get_winner()),
+        }
+    }
+}
+
+impl TournamentReport {
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// a pot or a country a group can only hold one team of
+#[derive(Clone, Copy)]
+enum Slot {
+    Pot(usize),
+    Country(u32),
+}
+
+/// put the drawn teams into groups, as indexes into `drawn`
+/// a group takes one team from each pot, and one from each country if countries are kept apart
+/// each team goes into the first group free of both, when there's none the teams of two groups
+/// are swapped along a chain of pots and countries to free one up
+/// that works whenever no pot or country has more teams than there are groups, `None` otherwise
+fn place_in_groups(
+    drawn: &[(usize, usize, Option<u32>)],
+    group_count: usize,
+) -> Option<Vec<Vec<usize>>> {
+    // group of each team placed so far
+    let mut placed = vec![None; drawn.len()];
+    for at in 0..drawn.len() {
+        let (_, pot, country) = drawn[at];
+        let free = |placed: &[Option<usize>], slot| {
+            (0..group_count)
+                .filter(|&g| find_in_group(drawn, placed, g, slot).is_none())
+                .collect::<Vec<_>>()
+        };
+        let pot_free = free(&placed, Slot::Pot(pot));
+        let group = match country {
+            None => *pot_free.first()?,
+            Some(country) => {
+                let slot = Slot::Country(country);
+                let country_free = free(&placed, slot);
+                match pot_free.iter().find(|g| country_free.contains(g)) {
+                    Some(&g) => g,
+                    None => {
+                        // the country has a team in `a` but not in `b`, starting from it follow
+                        // teams in `a` and `b` in turn and swap them over, which frees `a` of it
+                        let (a, b) = (*pot_free.first()?, *country_free.first()?);
+                        let mut chain = Vec::new();
+                        let (mut slot, mut group) = (slot, a);
+                        while let Some(d) = find_in_group(drawn, &placed, group, slot) {
+                            chain.push(d);
+                            slot = match slot {
+                                Slot::Country(_) => Slot::Pot(drawn[d].1),
+                                Slot::Pot(_) => match drawn[d].2 {
+                                    Some(country) => Slot::Country(country),
+                                    None => break,
+                                },
+                            };
+                            group = if group == a { b } else { a };
+                        }
+                        for d in chain {
+                            placed[d] = if placed[d] == Some(a) {
+                                Some(b)
+                            } else {
+                                Some(a)
+                            };
+                        }
+                        a
+                    }
+                }
+            }
+        };
+        placed[at] = Some(group);
+    }
+    let mut groups = vec![Vec::new(); group_count];
+    for (d, group) in placed.into_iter().enumerate() {
+        groups[group?].push(d);
+    }
+    Some(groups)
+}
+
+/// return the team placed in group `g` that takes up `slot`
+fn find_in_group(
+    drawn: &[(usize, usize, Option<u32>)],
+    placed: &[Option<usize>],
+    g: usize,
+    slot: Slot,
+) -> Option<usize> {
+    (0..drawn.len()).find(|&d| {
+        placed[d] == Some(g)
+            && match slot {
+                Slot::Pot(pot) => drawn[d].1 == pot,
+                Slot::Country(country) => drawn[d].2 == Some(country),
+            }
+    })
+}
+
+/// return name of a group by its index, "group A" for the first
+fn get_group_name(index: usize) -> String {
+    match index < 26 {
+        true => format!("group {}", (b'A' + index as u8) as char),
+        false => format!("group {}", index + 1),
+    }
+}
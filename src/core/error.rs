@@ -0,0 +1,112 @@
+use super::squad;
+
+/// everything that can stop a match from being set up or played
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FootsimError {
+    // squad can't be played, with every problem found
+    Lineup(squad::LineupError),
+    // player rating outside 1 - 255
+    Attribute {
+        player_id: u32,
+        attribute: &'static str,
+    },
+    // manager rating outside 1 - 255
+    ManagerAttribute {
+        manager_id: u32,
+        attribute: &'static str,
+    },
+    // tactic slider outside 1 - 255
+    Tactic {
+        tactic: &'static str,
+    },
+    // same player picked by both teams
+    PlayerInBothSquads(u32),
+    // condition that isn't a number
+    Condition {
+        player_id: u32,
+    },
+    // `play` called on a match that's been played
+    AlreadyPlayed,
+    // competition with fewer teams than it needs, as (teams, teams needed)
+    NotEnoughTeams(usize, usize),
+    // same team entered into a competition more than once
+    DuplicateTeam(u32),
+    // player on the roster of a team whose `team_id` is another team
+    PlayerNotInTeam {
+        team_id: u32,
+        player_id: u32,
+    },
+    // manager of a team whose `team_id` is another team
+    ManagerNotInTeam {
+        team_id: u32,
+        manager_id: u32,
+    },
+    // no group draw keeps teams of the same country apart
+    NoValidDraw,
+}
+
+impl From<squad::LineupError> for FootsimError {
+    fn from(error: squad::LineupError) -> Self {
+        FootsimError::Lineup(error)
+    }
+}
+
+impl std::fmt::Display for FootsimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FootsimError::Lineup(error) => write!(f, "{}", error),
+            FootsimError::Attribute {
+                player_id,
+                attribute,
+            } => write!(
+                f,
+                "{} of player {} is 0, ratings go from 1 to 255",
+                attribute, player_id
+            ),
+            FootsimError::ManagerAttribute {
+                manager_id,
+                attribute,
+            } => write!(
+                f,
+                "{} of manager {} is 0, ratings go from 1 to 255",
+                attribute, manager_id
+            ),
+            FootsimError::Tactic { tactic } => {
+                write!(f, "tactic {} is 0, tactics go from 1 to 255", tactic)
+            }
+            FootsimError::PlayerInBothSquads(id) => {
+                write!(f, "player {} is picked by both teams", id)
+            }
+            FootsimError::Condition { player_id } => {
+                write!(f, "condition of player {} isn't a number", player_id)
+            }
+            FootsimError::AlreadyPlayed => write!(f, "the match has already been played"),
+            FootsimError::NotEnoughTeams(teams, needed) => {
+                write!(
+                    f,
+                    "{} teams entered, the competition needs {}",
+                    teams, needed
+                )
+            }
+            FootsimError::DuplicateTeam(id) => write!(f, "team {} is entered more than once", id),
+            FootsimError::PlayerNotInTeam { team_id, player_id } => write!(
+                f,
+                "player {} is on the roster of team {} but belongs to another team",
+                player_id, team_id
+            ),
+            FootsimError::ManagerNotInTeam {
+                team_id,
+                manager_id,
+            } => write!(
+                f,
+                "manager {} manages team {} but belongs to another team",
+                manager_id, team_id
+            ),
+            FootsimError::NoValidDraw => {
+                write!(f, "no draw of the groups keeps teams of a country apart")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FootsimError {}
@@ -0,0 +1,161 @@
+use super::{injury, tactics};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Side {
+    Home,
+    Away,
+}
+
+impl Side {
+    pub fn opponent(self) -> Self {
+        match self {
+            Side::Home => Side::Away,
+            Side::Away => Side::Home,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Card {
+    Yellow,
+    Red,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CardReason {
+    Foul,
+    // straight red for a dangerous challenge
+    SeriousFoulPlay,
+    // red for a booked player picking up another yellow
+    SecondYellow,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GoalType {
+    OpenPlay,
+    // header from a cross
+    Header,
+    Penalty,
+    DirectFreeKick,
+    OwnGoal,
+}
+
+/// where a shot is taken from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShotOrigin {
+    // inside the box
+    OpenPlay,
+    // header from a cross
+    Header,
+    // from outside the box
+    LongShot,
+    // direct free kick
+    FreeKick,
+    Penalty,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubReason {
+    Fatigue,
+    // booked player likely to pick up a second yellow
+    YellowCardRisk,
+    Injury,
+    // chasing or protecting the result
+    Scoreline,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventKind {
+    KickOff,
+    Cross {
+        player_id: u32,
+    },
+    // `xg` is the chance of the shot going in past an average keeper
+    // `assist_id` is the team mate who set the shot up with a key pass
+    Shot {
+        player_id: u32,
+        assist_id: Option<u32>,
+        on_target: bool,
+        origin: ShotOrigin,
+        xg: f32,
+    },
+    // keeper of the defending team saves a shot on target
+    Save {
+        keeper_id: u32,
+        shooter_id: u32,
+    },
+    // for own goals `player_id` is the opponent who scored
+    Goal {
+        player_id: u32,
+        assist_id: Option<u32>,
+        goal_type: GoalType,
+    },
+    // player wins the ball off an opponent cleanly
+    Tackle {
+        player_id: u32,
+        tackled_id: u32,
+    },
+    Foul {
+        player_id: u32,
+        fouled_id: u32,
+    },
+    // a second yellow is shown as a yellow followed by a red
+    Card {
+        player_id: u32,
+        card: Card,
+        reason: CardReason,
+    },
+    Corner {
+        player_id: u32,
+    },
+    FreeKick {
+        player_id: u32,
+    },
+    Penalty {
+        player_id: u32,
+    },
+    // injuries that force the player off end their match
+    Injury {
+        player_id: u32,
+        severity: injury::Severity,
+        days_out: u16,
+    },
+    Substitution {
+        player_off: u32,
+        player_on: u32,
+        reason: SubReason,
+    },
+    // manager changes the tactics of the team to these
+    TacticsChange {
+        tactics: tactics::Tactics,
+    },
+    HalfTime,
+    // normal time is over with the tie level, extra time to follow
+    ExtraTime,
+    ExtraTimeHalfTime,
+    // end of play, before any penalty shootout
+    FullTime,
+    // kick of a penalty shootout, `keeper_id` is the opponent in goal
+    ShootoutKick {
+        player_id: u32,
+        keeper_id: u32,
+        scored: bool,
+    },
+}
+
+/// something that happened during a match
+/// `side` is the team the event belongs to, `None` for events of the whole match (e.g. half time)
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchEvent {
+    pub minute: u8,
+    pub side: Option<Side>,
+    pub kind: EventKind,
+}
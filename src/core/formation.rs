@@ -0,0 +1,100 @@
+use super::position;
+
+/// shape a team lines up in, each of the 11 slots of the starting eleven has a position
+/// players play the position of their slot, whatever their natural position
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Formation {
+    #[default]
+    FourFourTwo,
+    FourThreeThree,
+    ThreeFiveTwo,
+    FourTwoThreeOne,
+    FiveThreeTwo,
+    // any other shape, slots in the order of the starting eleven
+    Custom([position::Position; 11]),
+}
+
+impl Formation {
+    /// return position of each slot, keeper first and then from left to right by line
+    /// custom formations keep their own order
+    pub fn slots(&self) -> [position::Position; 11] {
+        use position::Position::*;
+        match self {
+            Formation::FourFourTwo => [
+                Goalkeeper,
+                LeftBack,
+                CenterBack,
+                CenterBack,
+                RightBack,
+                LeftMidfield,
+                CenterMidfield,
+                CenterMidfield,
+                RightMidfield,
+                Striker,
+                Striker,
+            ],
+            Formation::FourThreeThree => [
+                Goalkeeper,
+                LeftBack,
+                CenterBack,
+                CenterBack,
+                RightBack,
+                CenterMidfield,
+                DefensiveMidfield,
+                CenterMidfield,
+                LeftWing,
+                Striker,
+                RightWing,
+            ],
+            Formation::ThreeFiveTwo => [
+                Goalkeeper,
+                CenterBack,
+                CenterBack,
+                CenterBack,
+                LeftWingBack,
+                CenterMidfield,
+                DefensiveMidfield,
+                CenterMidfield,
+                RightWingBack,
+                Striker,
+                Striker,
+            ],
+            Formation::FourTwoThreeOne => [
+                Goalkeeper,
+                LeftBack,
+                CenterBack,
+                CenterBack,
+                RightBack,
+                DefensiveMidfield,
+                DefensiveMidfield,
+                LeftWing,
+                AttackingMidfield,
+                RightWing,
+                Striker,
+            ],
+            Formation::FiveThreeTwo => [
+                Goalkeeper,
+                LeftWingBack,
+                CenterBack,
+                CenterBack,
+                CenterBack,
+                RightWingBack,
+                CenterMidfield,
+                CenterMidfield,
+                CenterMidfield,
+                Striker,
+                Striker,
+            ],
+            Formation::Custom(slots) => *slots,
+        }
+    }
+
+    /// return number of keepers in the formation, a playable formation has exactly one
+    pub fn goalkeepers(&self) -> usize {
+        self.slots()
+            .iter()
+            .filter(|&&p| p == position::Position::Goalkeeper)
+            .count()
+    }
+}
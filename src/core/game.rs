@@ -1,225 +1,2189 @@
-use std::sync::Arc;
-use std::sync::RwLock;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
-use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
+use super::event;
 use super::player;
 use super::tactics;
-use super::{position, squad, style};
+use super::{
+    error, formation, injury, manager_ai, position, rating, rules, squad, style, substitution,
+};
 
-pub struct Game<'a> {
-    home: squad::Squad<'a>,
-    away: squad::Squad<'a>,
+/// length of a half in minutes
+pub const HALF_LENGTH: u8 = 45;
+/// length of each of the two periods of extra time in minutes
+pub const EXTRA_TIME_LENGTH: u8 = 15;
+/// length of a spell of play in minutes, the state of the match is re-evaluated after every spell
+pub const SPELL_LENGTH: u8 = 5;
+/// chance of an open play shot being set up by a team mate
+const ASSIST_CHANCE: f64 = 0.7;
+/// chance of a cross that isn't won in the air ending in an own goal, about one every twenty
+/// matches for each team
+const OWN_GOAL_CHANCE: f64 = 0.002;
+/// condition a player loses to a knock they play on with
+const KNOCK_CONDITION: f32 = 10.0;
+/// kicks each team takes in a penalty shootout before sudden death
+const SHOOTOUT_KICKS: usize = 5;
+/// defensive strength of a back four of average players, shots against it keep their xG
+const AVERAGE_DEF_STRENGTH: f32 = 1400.0;
+/// chance of a pass that goes astray being won by an opponent rather than going out of play
+const INTERCEPTION_CHANCE: f64 = 0.1;
+
+/// `R` is the random number generator driving the match
+/// seeded generators replay the exact same match for the same squads
+pub struct Game<R = StdRng> {
+    home: squad::Squad,
+    away: squad::Squad,
     home_stats: GameStats,
     away_stats: GameStats,
-    rng: Arc<RwLock<ThreadRng>>,
+    // match timeline, ordered by minute
+    events: Vec<event::MatchEvent>,
+    // player condition (100 = fresh), keyed by player id
+    condition: HashMap<u32, f32>,
+    sub_policy: substitution::SubstitutionPolicy,
+    // `None` plays the whole match with the tactics each team started with
+    manager_ai: Option<manager_ai::ManagerAI>,
+    // tactics each team started with, managers adjust from them
+    home_plan: tactics::Tactics,
+    away_plan: tactics::Tactics,
+    rules: rules::MatchRules,
+    // substitution windows used by each team
+    home_windows: u8,
+    away_windows: u8,
+    // players of either team taken off, they can't come back on
+    substituted: Vec<Arc<player::Player>>,
+    // (player id, position of their slot) of every player who took the pitch for each team,
+    // at kick off or when they came on
+    home_positions: Vec<(u32, position::Position)>,
+    away_positions: Vec<(u32, position::Position)>,
+    rng: RwLock<R>,
+}
+
+/// a shot about to be taken
+struct Attempt<'a> {
+    minute: u8,
+    shooter: &'a player::Player,
+    assister: Option<&'a player::Player>,
+    goal_type: event::GoalType,
+    origin: event::ShotOrigin,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Half {
+    First,
+    Second,
+    ExtraTimeFirst,
+    ExtraTimeSecond,
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameStats {
+    pub possession: f32,
+    pub crosses: u8,
+    pub shots: u8,
+    pub shots_on_target: u8,
+    // expected goals of every shot, the penalty shootout not included
+    pub xg: f32,
+    // (player id, xG) of each shooter, in order of their first shot
+    pub xg_by_player: Vec<(u32, f32)>,
+    pub goals: u8,
+    pub freekicks: u8,
+    pub penalties: u8,
+    pub corners: u8,
+    pub fouls: u8,
+    pub tackles: u8,
+    pub passes: u16,
+    pub passes_completed: u16,
+    // passes of the opponent won by the team
+    pub interceptions: u8,
+    pub saves: u8,
+    // km covered by everyone who played
+    pub distance: f32,
+    // player id of each yellow card, booked twice for a second yellow
+    pub yellow_cards: Vec<u32>,
+    pub red_cards: Vec<u32>,
+    // every card shown, in order
+    pub bookings: Vec<Booking>,
+    // player id of the scorer of each goal, own goals not included
+    pub scorers: Vec<u32>,
+    // player id of the assister of each assisted goal
+    pub assists: Vec<u32>,
+    // player id of the opponent who put the ball in their own net
+    pub own_goals: Vec<u32>,
+    // (player off, player on) of each substitution
+    pub substitutions: Vec<(u32, u32)>,
+    pub injuries: Vec<injury::Injury>,
+    // (player id, scored) of each penalty shootout kick, in order, not counted as goals
+    pub shootout: Vec<(u32, bool)>,
+    // stats of each half on its own, in order of play, periods of extra time included
+    pub halves: Vec<GameStats>,
+    // stats of everyone who played, the starting eleven first, then the subs in the order
+    // they came on, adding up to the team totals
+    pub players: Vec<PlayerMatchStats>,
 }
 
-#[derive(Default, Clone)]
-pub struct GameStats {
-    pub possession: f32,
-    pub crosses: u8,
-    pub shots: u8,
-    pub shots_on_target: u8,
-    pub goals: u8,
-    pub freekicks: u8,
-    pub penalties: u8,
-    pub corners: u8,
-    pub fouls: u8,
-    pub yellow_cards: Vec<u32>,
-    pub red_cards: Vec<u32>,
-}
+/// what a player did in the match
+#[derive(Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerMatchStats {
+    pub player_id: u32,
+    pub minutes: u8,
+    pub shots: u8,
+    pub shots_on_target: u8,
+    pub xg: f32,
+    // own goals not included
+    pub goals: u8,
+    pub assists: u8,
+    pub passes: u16,
+    pub passes_completed: u16,
+    pub crosses: u8,
+    pub tackles: u8,
+    pub interceptions: u8,
+    pub fouls: u8,
+    // fouls suffered
+    pub fouled: u8,
+    pub saves: u8,
+    // a second yellow counts as a yellow and a red
+    pub yellow_cards: u8,
+    pub red_cards: u8,
+    // km
+    pub distance: f32,
+}
+
+/// a card shown to a player
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Booking {
+    pub player_id: u32,
+    pub minute: u8,
+    pub card: event::Card,
+    pub reason: event::CardReason,
+}
+
+impl GameStats {
+    /// return (player id, goals) of each scorer, in order of their first goal
+    pub fn get_goals_by_player(&self) -> Vec<(u32, u8)> {
+        count_by_player(&self.scorers)
+    }
+
+    /// return (player id, assists) of each assister, in order of their first assist
+    pub fn get_assists_by_player(&self) -> Vec<(u32, u8)> {
+        count_by_player(&self.assists)
+    }
+
+    /// return (player id, yellow cards, red cards) of each booked player, in order of their first card
+    /// for a competition to count towards suspensions
+    pub fn get_cards_by_player(&self) -> Vec<(u32, u8, u8)> {
+        let mut cards: Vec<(u32, u8, u8)> = Vec::new();
+        for booking in self.bookings.iter() {
+            let i = match cards.iter().position(|(id, _, _)| *id == booking.player_id) {
+                Some(i) => i,
+                None => {
+                    cards.push((booking.player_id, 0, 0));
+                    cards.len() - 1
+                }
+            };
+            match booking.card {
+                event::Card::Yellow => cards[i].1 += 1,
+                event::Card::Red => cards[i].2 += 1,
+            }
+        }
+        cards
+    }
+
+    /// return penalties scored in the shootout
+    pub fn get_shootout_goals(&self) -> u8 {
+        self.shootout.iter().filter(|(_, scored)| *scored).count() as u8
+    }
+
+    /// return whether the player has been sent off or forced off injured
+    fn has_left_pitch(&self, player_id: u32) -> bool {
+        self.red_cards.contains(&player_id)
+            || self
+                .injuries
+                .iter()
+                .any(|i| i.player_id == player_id && i.severity.forces_off())
+    }
+
+    /// return stats of a player of the team, `None` if they didn't play
+    pub fn get_player_stats(&self, player_id: u32) -> Option<&PlayerMatchStats> {
+        self.players.iter().find(|p| p.player_id == player_id)
+    }
+
+    /// return stats of a player of the team, starting a line for them if they have none yet
+    fn get_player_stats_mut(&mut self, player_id: u32) -> &mut PlayerMatchStats {
+        let i = match self.players.iter().position(|p| p.player_id == player_id) {
+            Some(i) => i,
+            None => {
+                self.players.push(PlayerMatchStats {
+                    player_id,
+                    ..Default::default()
+                });
+                self.players.len() - 1
+            }
+        };
+        &mut self.players[i]
+    }
+
+    /// add passes a player attempted and completed
+    fn add_passes(&mut self, player_id: u32, attempted: u16, completed: u16) {
+        self.passes = self.passes.saturating_add(attempted);
+        self.passes_completed = self.passes_completed.saturating_add(completed);
+        let player = self.get_player_stats_mut(player_id);
+        player.passes = player.passes.saturating_add(attempted);
+        player.passes_completed = player.passes_completed.saturating_add(completed);
+    }
+
+    /// add passes of the opponent a player won
+    fn add_interceptions(&mut self, player_id: u32, interceptions: u8) {
+        self.interceptions = self.interceptions.saturating_add(interceptions);
+        let player = self.get_player_stats_mut(player_id);
+        player.interceptions = player.interceptions.saturating_add(interceptions);
+    }
+
+    /// add minutes a player spent on the pitch and the km they covered
+    fn add_time_played(&mut self, player_id: u32, minutes: u8, distance: f32) {
+        self.distance += distance;
+        let player = self.get_player_stats_mut(player_id);
+        player.minutes = player.minutes.saturating_add(minutes);
+        player.distance += distance;
+    }
+
+    /// add an event of this team to the stats
+    fn record(&mut self, event: &event::MatchEvent) {
+        match event.kind {
+            event::EventKind::Cross { player_id, .. } => {
+                self.crosses = self.crosses.saturating_add(1);
+                let player = self.get_player_stats_mut(player_id);
+                player.crosses = player.crosses.saturating_add(1);
+            }
+            event::EventKind::Shot {
+                player_id,
+                on_target,
+                xg,
+                ..
+            } => {
+                self.shots = self.shots.saturating_add(1);
+                if on_target {
+                    self.shots_on_target = self.shots_on_target.saturating_add(1);
+                }
+                self.xg += xg;
+                match self
+                    .xg_by_player
+                    .iter_mut()
+                    .find(|(id, _)| *id == player_id)
+                {
+                    Some((_, total)) => *total += xg,
+                    None => self.xg_by_player.push((player_id, xg)),
+                }
+                let player = self.get_player_stats_mut(player_id);
+                player.shots = player.shots.saturating_add(1);
+                player.shots_on_target = player.shots_on_target.saturating_add(on_target as u8);
+                player.xg += xg;
+            }
+            event::EventKind::Goal {
+                player_id,
+                assist_id,
+                goal_type,
+            } => {
+                self.goals = self.goals.saturating_add(1);
+                match goal_type {
+                    // the scorer is an opponent, with no line in these stats
+                    event::GoalType::OwnGoal => self.own_goals.push(player_id),
+                    _ => {
+                        self.scorers.push(player_id);
+                        let player = self.get_player_stats_mut(player_id);
+                        player.goals = player.goals.saturating_add(1);
+                    }
+                }
+                if let Some(assist_id) = assist_id {
+                    self.assists.push(assist_id);
+                    let player = self.get_player_stats_mut(assist_id);
+                    player.assists = player.assists.saturating_add(1);
+                }
+            }
+            event::EventKind::Foul { player_id, .. } => {
+                self.fouls = self.fouls.saturating_add(1);
+                let player = self.get_player_stats_mut(player_id);
+                player.fouls = player.fouls.saturating_add(1);
+            }
+            event::EventKind::Tackle { player_id, .. } => {
+                self.tackles = self.tackles.saturating_add(1);
+                let player = self.get_player_stats_mut(player_id);
+                player.tackles = player.tackles.saturating_add(1);
+            }
+            event::EventKind::Save { keeper_id, .. } => {
+                self.saves = self.saves.saturating_add(1);
+                let player = self.get_player_stats_mut(keeper_id);
+                player.saves = player.saves.saturating_add(1);
+            }
+            event::EventKind::Card {
+                player_id,
+                card,
+                reason,
+            } => {
+                let player = self.get_player_stats_mut(player_id);
+                match card {
+                    event::Card::Yellow => {
+                        player.yellow_cards = player.yellow_cards.saturating_add(1)
+                    }
+                    event::Card::Red => player.red_cards = player.red_cards.saturating_add(1),
+                }
+                match card {
+                    event::Card::Yellow => self.yellow_cards.push(player_id),
+                    event::Card::Red => self.red_cards.push(player_id),
+                }
+                self.bookings.push(Booking {
+                    player_id,
+                    minute: event.minute,
+                    card,
+                    reason,
+                });
+            }
+            event::EventKind::Corner { .. } => self.corners = self.corners.saturating_add(1),
+            event::EventKind::FreeKick { .. } => self.freekicks = self.freekicks.saturating_add(1),
+            event::EventKind::Penalty { .. } => self.penalties = self.penalties.saturating_add(1),
+            event::EventKind::Substitution {
+                player_off,
+                player_on,
+                ..
+            } => {
+                self.substitutions.push((player_off, player_on));
+                self.get_player_stats_mut(player_on);
+            }
+            event::EventKind::Injury {
+                player_id,
+                severity,
+                days_out,
+            } => self.injuries.push(injury::Injury {
+                player_id,
+                minute: event.minute,
+                severity,
+                days_out,
+            }),
+            event::EventKind::ShootoutKick {
+                player_id, scored, ..
+            } => self.shootout.push((player_id, scored)),
+            _ => {}
+        }
+    }
+}
+
+impl Game {
+    pub fn new(
+        home_squad: squad::Squad,
+        away_squad: squad::Squad,
+    ) -> Result<Self, error::FootsimError> {
+        Self::with_rng(home_squad, away_squad, StdRng::from_entropy())
+    }
+
+    /// create a game that always plays out the same way for the same squads and seed
+    /// (for the same version of `rand`)
+    pub fn with_seed(
+        home_squad: squad::Squad,
+        away_squad: squad::Squad,
+        seed: u64,
+    ) -> Result<Self, error::FootsimError> {
+        Self::with_rng(home_squad, away_squad, StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> Game<R> {
+    /// fails if either squad can't be played, see `validate`
+    pub fn with_rng(
+        home_squad: squad::Squad,
+        away_squad: squad::Squad,
+        rng: R,
+    ) -> Result<Self, error::FootsimError> {
+        validate(&home_squad, &away_squad)?;
+        let condition = home_squad
+            .players
+            .iter()
+            .chain(home_squad.subs.iter())
+            .chain(away_squad.players.iter())
+            .chain(away_squad.subs.iter())
+            .map(|p| (p.id, 100.0))
+            .collect();
+        Ok(Self {
+            home_plan: home_squad.tactics.clone(),
+            away_plan: away_squad.tactics.clone(),
+            home: home_squad,
+            away: away_squad,
+            home_stats: GameStats::default(),
+            away_stats: GameStats::default(),
+            events: Vec::new(),
+            condition,
+            sub_policy: substitution::SubstitutionPolicy::default(),
+            manager_ai: None,
+            rules: rules::MatchRules::default(),
+            home_windows: 0,
+            away_windows: 0,
+            substituted: Vec::new(),
+            home_positions: Vec::new(),
+            away_positions: Vec::new(),
+            rng: RwLock::new(rng),
+        })
+    }
+
+    pub fn set_substitution_policy(&mut self, policy: substitution::SubstitutionPolicy) {
+        self.sub_policy = policy;
+    }
+
+    /// let the managers change tactics during the match, the same way for both
+    pub fn set_manager_ai(&mut self, manager_ai: manager_ai::ManagerAI) {
+        self.manager_ai = Some(manager_ai);
+    }
+
+    /// set condition (0 - 100) a player starts the match with, e.g. carried over from the last match
+    /// players not in either squad are ignored
+    pub fn set_condition(
+        &mut self,
+        player_id: u32,
+        condition: f32,
+    ) -> Result<(), error::FootsimError> {
+        if condition.is_nan() {
+            return Err(error::FootsimError::Condition { player_id });
+        }
+        if let Some(c) = self.condition.get_mut(&player_id) {
+            *c = condition.clamp(0.0, 100.0);
+        }
+        Ok(())
+    }
+
+    pub fn set_rules(&mut self, rules: rules::MatchRules) {
+        self.rules = rules;
+    }
+
+    /// play the match, once
+    pub fn play(&mut self) -> Result<(), error::FootsimError> {
+        if !self.events.is_empty() {
+            return Err(error::FootsimError::AlreadyPlayed);
+        }
+        // everyone in the starting eleven has a line in the stats, however little they do
+        for side in [event::Side::Home, event::Side::Away] {
+            let squad = self.squad(side);
+            let eleven = squad
+                .players
+                .iter()
+                .map(|p| p.id)
+                .zip(squad.formation.slots())
+                .collect::<Vec<_>>();
+            for &(id, _) in &eleven {
+                self.update_stats(side, |stats| {
+                    stats.get_player_stats_mut(id);
+                });
+            }
+            *self.positions_mut(side) = eleven;
+        }
+        self.play_half(Half::First);
+        self.add_event(HALF_LENGTH, None, event::EventKind::HalfTime);
+        // half time: condition, bookings and score carry over into the second half
+        self.play_half(Half::Second);
+        let mut end = HALF_LENGTH * 2;
+        // a level knockout tie goes to extra time, then penalties
+        if self.rules.knockout && self.rules.extra_time && self.get_tie_winner().is_none() {
+            self.add_event(end, None, event::EventKind::ExtraTime);
+            self.play_half(Half::ExtraTimeFirst);
+            self.add_event(
+                end + EXTRA_TIME_LENGTH,
+                None,
+                event::EventKind::ExtraTimeHalfTime,
+            );
+            self.play_half(Half::ExtraTimeSecond);
+            end += EXTRA_TIME_LENGTH * 2;
+        }
+        self.add_event(end, None, event::EventKind::FullTime);
+        if self.rules.knockout && self.get_tie_winner().is_none() {
+            self.play_shootout(end);
+        }
+        Ok(())
+    }
+
+    pub fn get_home_stats(&self) -> GameStats {
+        self.home_stats.to_owned()
+    }
+    pub fn get_away_stats(&self) -> GameStats {
+        self.away_stats.to_owned()
+    }
+
+    /// return a player of either team, on the pitch, on the bench or taken off
+    pub fn get_player(&self, player_id: u32) -> Option<&player::Player> {
+        [&self.home, &self.away]
+            .into_iter()
+            .flat_map(|squad| squad.players.iter().chain(squad.subs.iter()))
+            .chain(self.substituted.iter())
+            .find(|p| p.id == player_id)
+            .map(|p| p.as_ref())
+    }
+
+    /// return match timeline, ordered by minute
+    pub fn get_events(&self) -> &[event::MatchEvent] {
+        &self.events
+    }
+
+    /// return the tactics the team is playing with, changed from kick off by the manager AI
+    pub fn get_tactics(&self, side: event::Side) -> &tactics::Tactics {
+        &self.squad(side).tactics
+    }
+
+    /// return (player id, position) of every player who took the pitch for the team, starters in
+    /// order of their slots then substitutes in the order they came on
+    /// each plays the position of the slot they were in at kick off or when they came on
+    pub fn get_participants(&self, side: event::Side) -> Vec<(u32, position::Position)> {
+        match side {
+            event::Side::Home => self.home_positions.clone(),
+            event::Side::Away => self.away_positions.clone(),
+        }
+    }
+
+    /// return rating of every player who took the pitch, with the man of the match
+    pub fn get_ratings(&self) -> rating::MatchRatings {
+        rating::MatchRatings::from_events(
+            &self.get_participants(event::Side::Home),
+            &self.get_participants(event::Side::Away),
+            &self.events,
+        )
+    }
+
+    /// return condition (100 = fresh) of a player in either squad
+    pub fn get_condition(&self, player_id: u32) -> Option<f32> {
+        self.condition.get(&player_id).copied()
+    }
+
+    /// return condition of every player in either squad, ordered by player id
+    /// after `play` this is the condition at full time
+    pub fn get_conditions(&self) -> Vec<(u32, f32)> {
+        let mut conditions = self
+            .condition
+            .iter()
+            .map(|(&id, &c)| (id, c))
+            .collect::<Vec<_>>();
+        conditions.sort_by_key(|&(id, _)| id);
+        conditions
+    }
+
+    /// return penalty shootout score (home, away), `None` if there was no shootout
+    pub fn get_shootout_score(&self) -> Option<(u8, u8)> {
+        if self.home_stats.shootout.is_empty() {
+            return None;
+        }
+        Some((
+            self.home_stats.get_shootout_goals(),
+            self.away_stats.get_shootout_goals(),
+        ))
+    }
+
+    /// return the score over both legs (home, away), from this match's side
+    /// `None` unless this match is the second leg of a tie
+    pub fn get_aggregate_score(&self) -> Option<(u16, u16)> {
+        let (first_home, first_away) = self.rules.first_leg?;
+        Some((
+            first_home as u16 + self.home_stats.goals as u16,
+            first_away as u16 + self.away_stats.goals as u16,
+        ))
+    }
+
+    /// return the team that wins the match, or goes through in a knockout tie
+    /// `None` for a draw
+    pub fn get_winner(&self) -> Option<event::Side> {
+        self.get_tie_winner()
+            .or_else(|| match self.get_shootout_score()? {
+                (home, away) if home > away => Some(event::Side::Home),
+                (home, away) if home < away => Some(event::Side::Away),
+                _ => None,
+            })
+    }
+
+    /// return half time score (home, away)
+    pub fn get_half_time_score(&self) -> Option<(u8, u8)> {
+        let home = self.home_stats.halves.first()?;
+        let away = self.away_stats.halves.first()?;
+        Some((home.goals, away.goals))
+    }
+
+    fn play_half(&mut self, half: Half) {
+        log::debug!("playing {:?} half", half);
+        let (start, length, kick_off) = match half {
+            Half::First => (0, HALF_LENGTH, event::Side::Home),
+            Half::Second => (HALF_LENGTH, HALF_LENGTH, event::Side::Away),
+            Half::ExtraTimeFirst => (HALF_LENGTH * 2, EXTRA_TIME_LENGTH, event::Side::Home),
+            Half::ExtraTimeSecond => (
+                HALF_LENGTH * 2 + EXTRA_TIME_LENGTH,
+                EXTRA_TIME_LENGTH,
+                event::Side::Away,
+            ),
+        };
+        self.home_stats.halves.push(GameStats::default());
+        self.away_stats.halves.push(GameStats::default());
+        if half != Half::First {
+            self.make_substitutions(start, true);
+            self.review_tactics(start, true);
+        }
+        self.add_event(start, Some(kick_off), event::EventKind::KickOff);
+
+        let spells = length / SPELL_LENGTH;
+        let (mut home_poss, mut away_poss) = (0.0, 0.0);
+        for spell in 0..spells {
+            if spell > 0 {
+                self.make_substitutions(start + spell * SPELL_LENGTH, false);
+                self.review_tactics(start + spell * SPELL_LENGTH, false);
+            }
+            let (home, away) = self.play_spell(start + spell * SPELL_LENGTH, SPELL_LENGTH);
+            home_poss += home;
+            away_poss += away;
+        }
+
+        // possession of the half, and of the match so far
+        for (stats, poss) in [
+            (&mut self.home_stats, home_poss),
+            (&mut self.away_stats, away_poss),
+        ] {
+            let poss = poss / spells as f32;
+            if let Some(half_stats) = stats.halves.last_mut() {
+                half_stats.possession = poss;
+            }
+            // weighted by the minutes played
+            stats.possession =
+                (stats.possession * start as f32 + poss * length as f32) / (start + length) as f32;
+        }
+    }
+
+    /// play `length` minutes from `start`, adding everything that happens to the timeline
+    /// return possession of each team during the spell
+    fn play_spell(&mut self, start: u8, length: u8) -> (f32, f32) {
+        // every rate is for a whole match of normal time, extra time is scaled the same way
+        let scale = length as f32 / (HALF_LENGTH * 2) as f32;
+        // stats so far, with possession of this spell
+        let (mut home_stats, mut away_stats) = (self.home_stats.clone(), self.away_stats.clone());
+        // get players
+        let home_players = self.get_players(event::Side::Home);
+        let away_players = self.get_players(event::Side::Away);
+        // --- calculations ---
+        // calculate possession of each team
+        let (home_poss, away_poss) = self.get_possession(
+            &self.home,
+            &self.away,
+            &home_players,
+            &away_players,
+            &home_stats,
+            &away_stats,
+        );
+        {
+            // modify stats
+            home_stats.possession = home_poss;
+            away_stats.possession = away_poss;
+        }
+
+        // calculate fouls based on possession
+        // based on fouls calculate freekicks, penalties and yellow cards and red cards
+        let home_fouls = self.get_fouls(&self.home, &home_players, &home_stats, scale);
+        let away_fouls = self.get_fouls(&self.away, &away_players, &away_stats, scale);
+        // players sent off or forced off injured during the spell take no further part in it
+        let mut sent_off = Vec::new();
+        // tired players pick up injuries on their own
+        let mut events = Vec::new();
+        events.extend(self.get_fatigue_injuries(
+            event::Side::Home,
+            &home_players,
+            &mut sent_off,
+            start,
+            length,
+        ));
+        events.extend(self.get_fatigue_injuries(
+            event::Side::Away,
+            &away_players,
+            &mut sent_off,
+            start,
+            length,
+        ));
+        events.extend(self.get_foul_events(
+            &home_fouls,
+            &away_fouls,
+            (&home_players, &away_players),
+            &mut sent_off,
+            start,
+            length,
+        ));
+
+        // --- get squad strength --
+        let home_def = self.get_squad_def_strength(&home_players);
+        let away_def = self.get_squad_def_strength(&away_players);
+        // gerial threat , defense
+        let home_aerial_threat = self.get_atk_aerial(&home_players);
+        let home_aerial_def = self.get_def_aerial(&home_players);
+        let away_aerial_threat = self.get_atk_aerial(&away_players);
+        let away_aerial_def = self.get_def_aerial(&away_players);
+        let home_wide_atk = self.get_wide_atk(&self.home, &home_players);
+        let away_wide_atk = self.get_wide_atk(&self.away, &away_players);
+        let home_wide_def = self.get_wide_def(&self.home, &home_players);
+        let away_wide_def = self.get_wide_def(&self.away, &away_players);
+        // trailing team commits more players forward
+        let home_intent = self.get_scoreline_intent(&home_stats, &away_stats);
+        let away_intent = self.get_scoreline_intent(&away_stats, &home_stats);
+
+        // based on possession get crosses, and headers from crosses
+        // based on possession and tactics calculate shots
+        let home_crosses =
+            self.get_crosses(&self.home, &home_stats, home_wide_atk, away_wide_def) * scale;
+        let away_crosses =
+            self.get_crosses(&self.away, &away_stats, away_wide_atk, home_wide_def) * scale;
+        let home_shots = self.get_shots(&self.home, &home_players, away_def, home_intent) * scale;
+        let away_shots = self.get_shots(&self.away, &away_players, home_def, away_intent) * scale;
+        let (home_attack, mut home_attempts) = self.get_attack_events(
+            event::Side::Home,
+            &home_players,
+            &away_players,
+            (home_crosses, home_shots),
+            self.get_header_chance(home_aerial_threat, away_aerial_def),
+            &sent_off,
+            start,
+            length,
+        );
+        let (away_attack, mut away_attempts) = self.get_attack_events(
+            event::Side::Away,
+            &away_players,
+            &home_players,
+            (away_crosses, away_shots),
+            self.get_header_chance(away_aerial_threat, home_aerial_def),
+            &sent_off,
+            start,
+            length,
+        );
+        // penalties and direct free kicks from fouls
+        home_attempts.extend(self.get_set_piece_attempts(
+            event::Side::Home,
+            &home_players,
+            &events,
+        ));
+        away_attempts.extend(self.get_set_piece_attempts(
+            event::Side::Away,
+            &away_players,
+            &events,
+        ));
+        events.extend(home_attack);
+        events.extend(away_attack);
+        events.extend(self.get_shot_events(
+            event::Side::Home,
+            (&home_players, &away_players),
+            away_def,
+            home_attempts,
+            &sent_off,
+        ));
+        events.extend(self.get_shot_events(
+            event::Side::Away,
+            (&away_players, &home_players),
+            home_def,
+            away_attempts,
+            &sent_off,
+        ));
+
+        // based on possession of the opponent calculate tackles won
+        let home_tackles = self.get_tackles(&self.home, &home_players, &away_stats) * scale;
+        let away_tackles = self.get_tackles(&self.away, &away_players, &home_stats) * scale;
+        events.extend(self.get_tackle_events(
+            event::Side::Home,
+            (&home_players, &away_players),
+            home_tackles,
+            &sent_off,
+            start,
+            length,
+        ));
+        events.extend(self.get_tackle_events(
+            event::Side::Away,
+            (&away_players, &home_players),
+            away_tackles,
+            &sent_off,
+            start,
+            length,
+        ));
+
+        // based on possession calculate passes, the opponent wins some of those that go astray
+        let home_passes = self.get_passes(&self.home, &home_stats) * scale;
+        let away_passes = self.get_passes(&self.away, &away_stats) * scale;
+        let (mut home_interceptions, mut away_interceptions) = (Vec::new(), Vec::new());
+        let home_passing = self.get_passing(
+            (&self.home, &self.away),
+            (&home_players, &away_players),
+            home_passes,
+            &mut away_interceptions,
+            &sent_off,
+            start,
+            length,
+        );
+        let away_passing = self.get_passing(
+            (&self.away, &self.home),
+            (&away_players, &home_players),
+            away_passes,
+            &mut home_interceptions,
+            &sent_off,
+            start,
+            length,
+        );
+
+        // calculate corners
+        let home_corners = self.get_corners(&self.home, &home_stats) * scale;
+        let away_corners = self.get_corners(&self.away, &away_stats) * scale;
+        events.extend(self.get_corner_events(
+            event::Side::Home,
+            &home_players,
+            home_corners,
+            &sent_off,
+            start,
+            length,
+        ));
+        events.extend(self.get_corner_events(
+            event::Side::Away,
+            &away_players,
+            away_corners,
+            &sent_off,
+            start,
+            length,
+        ));
+
+        // add spell events to the timeline
+        events.sort_by_key(|e| e.minute);
+        for e in events {
+            self.add_event(e.minute, e.side, e.kind);
+        }
+        // passing and running are not on the timeline, they go straight into the stats
+        let home_time = self.get_time_played(&self.home, &home_players, &sent_off, start, length);
+        let away_time = self.get_time_played(&self.away, &away_players, &sent_off, start, length);
+        for (side, passing, interceptions, time) in [
+            (
+                event::Side::Home,
+                home_passing,
+                home_interceptions,
+                home_time,
+            ),
+            (
+                event::Side::Away,
+                away_passing,
+                away_interceptions,
+                away_time,
+            ),
+        ] {
+            for (id, attempted, completed) in passing {
+                self.update_stats(side, |stats| stats.add_passes(id, attempted, completed));
+            }
+            for (id, count) in count_by_player(&interceptions) {
+                self.update_stats(side, |stats| stats.add_interceptions(id, count));
+            }
+            for (id, minutes, distance) in time {
+                self.update_stats(side, |stats| stats.add_time_played(id, minutes, distance));
+            }
+        }
+
+        // players on the pitch tire over the spell, knocks take their toll too
+        self.drain_condition(event::Side::Home, length);
+        self.drain_condition(event::Side::Away, length);
+        let knocks = self
+            .events
+            .iter()
+            .filter(|e| e.minute >= start)
+            .filter_map(|e| match e.kind {
+                event::EventKind::Injury {
+                    player_id,
+                    severity: injury::Severity::Knock,
+                    ..
+                } => Some(player_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for id in knocks {
+            if let Some(condition) = self.condition.get_mut(&id) {
+                *condition = (*condition - KNOCK_CONDITION).max(0.0);
+            }
+        }
+        (home_poss, away_poss)
+    }
+
+    /// return the team ahead in the tie, over both legs and on away goals if the rules say so
+    /// `None` while the tie is level
+    fn get_tie_winner(&self) -> Option<event::Side> {
+        let (first_home, first_away) = self.rules.first_leg.unwrap_or((0, 0));
+        let home = first_home as u16 + self.home_stats.goals as u16;
+        let away = first_away as u16 + self.away_stats.goals as u16;
+        // the home team scored their first leg goals away from home
+        let (home, away) = match (home == away, self.rules.away_goals, self.rules.first_leg) {
+            (true, true, Some(_)) => (first_home as u16, self.away_stats.goals as u16),
+            _ => (home, away),
+        };
+        match home.cmp(&away) {
+            std::cmp::Ordering::Greater => Some(event::Side::Home),
+            std::cmp::Ordering::Less => Some(event::Side::Away),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// take penalties in turns until one team is ahead
+    /// `SHOOTOUT_KICKS` each, then sudden death
+    fn play_shootout(&mut self, minute: u8) {
+        log::debug!("penalty shootout");
+        let home_kickers = self.get_shootout_order(event::Side::Home);
+        let away_kickers = self.get_shootout_order(event::Side::Away);
+        if home_kickers.is_empty() || away_kickers.is_empty() {
+            return;
+        }
+        let home_keeper = self.get_shootout_keeper(&home_kickers);
+        let away_keeper = self.get_shootout_keeper(&away_kickers);
+        // coin toss for who kicks first
+        let first = match self.rng.write().unwrap().gen_bool(0.5) {
+            true => event::Side::Home,
+            false => event::Side::Away,
+        };
+
+        // (kicks taken, scored) of each team
+        let (mut home, mut away) = ((0, 0), (0, 0));
+        for kick in 0usize.. {
+            let side = match kick % 2 {
+                0 => first,
+                _ => first.opponent(),
+            };
+            let (kickers, keeper, tally) = match side {
+                event::Side::Home => (&home_kickers, &away_keeper, &mut home),
+                event::Side::Away => (&away_kickers, &home_keeper, &mut away),
+            };
+            // everyone kicks once before anybody kicks again
+            let kicker = &kickers[tally.0 % kickers.len()];
+            let scored = self.get_shootout_kick(kicker, keeper);
+            tally.0 += 1;
+            tally.1 += scored as usize;
+            self.add_event(
+                minute,
+                Some(side),
+                event::EventKind::ShootoutKick {
+                    player_id: kicker.id,
+                    keeper_id: keeper.id,
+                    scored,
+                },
+            );
+
+            let left = |tally: (usize, usize)| SHOOTOUT_KICKS.saturating_sub(tally.0);
+            let decided = match home.0 > SHOOTOUT_KICKS || away.0 > SHOOTOUT_KICKS {
+                // sudden death, after both teams have kicked
+                true => home.0 == away.0 && home.1 != away.1,
+                // one team can't catch up any more
+                false => home.1 + left(home) < away.1 || away.1 + left(away) < home.1,
+            };
+            if decided {
+                return;
+            }
+        }
+    }
+
+    /// return players of the team on the pitch in the order they take their penalties
+    /// calculated based on:
+    /// - set pieces
+    /// - shooting
+    /// - decision making
+    ///
+    /// keepers go last
+    fn get_shootout_order(&self, side: event::Side) -> Vec<player::Player> {
+        let mut players = self.get_players(side);
+        let score = |p: &player::Player| {
+            let taker = p.set_pieces as u32 * 2 + p.shooting as u32 * 2 + p.decision_making as u32;
+            match p.position {
+                position::Position::Goalkeeper => taker / 4,
+                _ => taker,
+            }
+        };
+        players.sort_by_key(|p| std::cmp::Reverse(score(p)));
+        players
+    }
+
+    /// return the player who goes in goal for the shootout, the keeper if still on the pitch
+    fn get_shootout_keeper(&self, players: &[player::Player]) -> player::Player {
+        players
+            .iter()
+            .max_by_key(|p| (p.position == position::Position::Goalkeeper, p.goalkeeping))
+            .cloned()
+            .unwrap_or_else(player::Player::new)
+    }
+
+    /// return whether a shootout penalty is scored
+    /// calculated based on:
+    /// - set pieces, shooting
+    /// - decision making: composure under pressure
+    /// - goalkeeping of the keeper
+    fn get_shootout_kick(&self, kicker: &player::Player, keeper: &player::Player) -> bool {
+        let mut rng = self.rng.write().unwrap();
+        let on_target = 0.7
+            + (kicker.set_pieces as f64 + kicker.shooting as f64) / 510.0 * 0.2
+            + kicker.decision_making as f64 / 255.0 * 0.08;
+        let save = keeper.goalkeeping as f64 * 0.001;
+        rng.gen_bool(probability(on_target)) && !rng.gen_bool(probability(save))
+    }
+
+    /// let the managers of both teams change tactics, at half time and every so often
+    fn review_tactics(&mut self, minute: u8, half_time: bool) {
+        let Some(manager_ai) = self.manager_ai.clone() else {
+            return;
+        };
+        for side in [event::Side::Home, event::Side::Away] {
+            let manager = self.squad(side).manager.clone();
+            // looks fall on the first stoppage after each interval is up
+            let interval = manager_ai.get_interval(&manager).max(1);
+            let due = minute / interval != minute.saturating_sub(SPELL_LENGTH) / interval;
+            if !half_time && !due {
+                continue;
+            }
+            let situation = self.get_situation(side, minute);
+            let plan = match side {
+                event::Side::Home => &self.home_plan,
+                event::Side::Away => &self.away_plan,
+            };
+            let tactics =
+                manager_ai.adjust(&manager, plan, &situation, &mut *self.rng.write().unwrap());
+            if tactics == self.squad(side).tactics {
+                continue;
+            }
+            log::debug!("{:?} tactics change: {:?}", side, tactics);
+            match side {
+                event::Side::Home => self.home.tactics = tactics.clone(),
+                event::Side::Away => self.away.tactics = tactics.clone(),
+            }
+            self.add_event(
+                minute,
+                Some(side),
+                event::EventKind::TacticsChange { tactics },
+            );
+        }
+    }
+
+    /// return the state of the match as the manager of the team sees it at `minute`
+    fn get_situation(&self, side: event::Side, minute: u8) -> manager_ai::Situation {
+        let (first_home, first_away) = self.rules.first_leg.unwrap_or((0, 0));
+        let home = first_home as i16 + self.home_stats.goals as i16;
+        let away = first_away as i16 + self.away_stats.goals as i16;
+        // condition of each player of the team still on the pitch
+        let on_pitch = |side: event::Side| {
+            let stats = self.stats(side);
+            self.squad(side)
+                .players
+                .iter()
+                .filter(|p| !stats.has_left_pitch(p.id))
+                .map(|p| self.condition.get(&p.id).copied().unwrap_or(100.0))
+                .collect::<Vec<_>>()
+        };
+        let (players, opp_players) = (on_pitch(side), on_pitch(side.opponent()));
+        manager_ai::Situation {
+            minute,
+            length: match minute >= HALF_LENGTH * 2 {
+                true => (HALF_LENGTH + EXTRA_TIME_LENGTH) * 2,
+                false => HALF_LENGTH * 2,
+            },
+            goal_difference: match side {
+                event::Side::Home => home - away,
+                event::Side::Away => away - home,
+            },
+            players: players.len() as u8,
+            opp_players: opp_players.len() as u8,
+            condition: players.iter().sum::<f32>() / players.len().max(1) as f32,
+        }
+    }
+
+    /// let the managers make changes at a stoppage
+    fn make_substitutions(&mut self, minute: u8, half_time: bool) {
+        for side in [event::Side::Home, event::Side::Away] {
+            self.make_team_substitutions(side, minute, half_time);
+        }
+    }
+
+    /// let the manager of the team make changes at a stoppage
+    /// injured players are replaced first, if no subs remain the team plays on short
+    /// changes at half time or for injuries don't use up one of the team's windows
+    fn make_team_substitutions(&mut self, side: event::Side, minute: u8, half_time: bool) {
+        let injured = self
+            .squad(side)
+            .players
+            .iter()
+            .filter(|p| {
+                let stats = self.stats(side);
+                !stats.red_cards.contains(&p.id) && stats.has_left_pitch(p.id)
+            })
+            .map(|p| p.id)
+            .collect::<Vec<_>>();
+        for player_id in injured {
+            if self.stats(side).substitutions.len() >= self.sub_policy.max_subs as usize {
+                break;
+            }
+            self.substitute(side, minute, player_id, event::SubReason::Injury, None);
+        }
+        self.cover_goal(side);
+
+        let windows = match side {
+            event::Side::Home => self.home_windows,
+            event::Side::Away => self.away_windows,
+        };
+        let subs_left = (self.sub_policy.max_subs as usize)
+            .saturating_sub(self.stats(side).substitutions.len());
+        if subs_left == 0
+            || self.squad(side).subs.is_empty()
+            || (!half_time && windows >= self.sub_policy.max_windows)
+        {
+            return;
+        }
+
+        let mut made = 0;
+        for (player_id, reason, area) in self.get_substitutions(side, minute, subs_left) {
+            if self.substitute(side, minute, player_id, reason, area) {
+                made += 1;
+            }
+        }
+        if made > 0 && !half_time {
+            match side {
+                event::Side::Home => self.home_windows += 1,
+                event::Side::Away => self.away_windows += 1,
+            }
+        }
+    }
+
+    /// return the changes the manager wants to make as (player off, reason, area of the
+    /// pitch to bring a player on for), at most `max` of them
+    /// better managers spot tired and booked players sooner and react to the scoreline earlier
+    fn get_substitutions(
+        &self,
+        side: event::Side,
+        minute: u8,
+        max: usize,
+    ) -> Vec<(u32, event::SubReason, Option<position::Area>)> {
+        let squad = self.squad(side);
+        let stats = self.stats(side);
+        let opp_stats = self.stats(side.opponent());
+        let policy = &self.sub_policy;
+        let skill = (squad.manager.tactical as f32 + squad.manager.management as f32)
+            / (u8::MAX as f32 * 2.0);
+        // most tired players first
+        let mut players = self.get_players(side);
+        players.sort_by(|a, b| {
+            self.get_condition_factor(a)
+                .partial_cmp(&self.get_condition_factor(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut rng = self.rng.write().unwrap();
+        let mut changes = Vec::new();
+        // tired players, poor managers leave them on for longer
+        let threshold = policy.fatigue_threshold - (1.0 - skill) * 15.0;
+        for p in players.iter() {
+            if self.get_condition_factor(p) * 100.0 < threshold
+                && rng.gen_bool((0.5 + 0.5 * skill) as f64)
+            {
+                changes.push((p.id, event::SubReason::Fatigue, None));
+            }
+        }
+        // booked players who are likely to foul again
+        if policy.protect_booked && minute >= HALF_LENGTH {
+            for p in players.iter() {
+                if stats.yellow_cards.contains(&p.id)
+                    && p.decision_making < u8::MAX / 2
+                    && rng.gen_bool(skill as f64)
+                {
+                    changes.push((p.id, event::SubReason::YellowCardRisk, None));
+                }
+            }
+        }
+        // chase or protect the result
+        let react_minute = policy
+            .scoreline_minute
+            .saturating_add(((1.0 - skill) * 20.0) as u8);
+        let scoreline_changes = self
+            .events
+            .iter()
+            .filter(|e| {
+                e.side == Some(side)
+                    && matches!(
+                        e.kind,
+                        event::EventKind::Substitution {
+                            reason: event::SubReason::Scoreline,
+                            ..
+                        }
+                    )
+            })
+            .count();
+        if minute >= react_minute && scoreline_changes < 2 {
+            let (off_area, on_area) = match stats.goals.cmp(&opp_stats.goals) {
+                // trailing: a defender makes way for an attacker
+                std::cmp::Ordering::Less => (position::Area::Defence, position::Area::Attack),
+                // leading late on: an attacker makes way for a midfielder
+                std::cmp::Ordering::Greater if minute >= react_minute + 15 => {
+                    (position::Area::Attack, position::Area::Midfield)
+                }
+                _ => (position::Area::Goal, position::Area::Goal),
+            };
+            let bench_has = squad.subs.iter().any(|p| p.position.area() == on_area);
+            if off_area != position::Area::Goal && bench_has {
+                if let Some(p) = players.iter().find(|p| {
+                    p.position.area() == off_area && !changes.iter().any(|(id, _, _)| *id == p.id)
+                }) {
+                    changes.push((p.id, event::SubReason::Scoreline, Some(on_area)));
+                }
+            }
+        }
+
+        let mut seen = Vec::new();
+        changes.retain(|(id, _, _)| {
+            let first = !seen.contains(id);
+            seen.push(*id);
+            first
+        });
+        changes.truncate(max);
+        changes
+    }
+
+    /// put the outfield player most familiar with goal between the posts if the keeper has
+    /// left the pitch without a replacement
+    fn cover_goal(&mut self, side: event::Side) {
+        let stats = match side {
+            event::Side::Home => &self.home_stats,
+            event::Side::Away => &self.away_stats,
+        };
+        let squad = match side {
+            event::Side::Home => &mut self.home,
+            event::Side::Away => &mut self.away,
+        };
+        let slots = squad.formation.slots();
+        let goal = match slots
+            .iter()
+            .position(|&p| p == position::Position::Goalkeeper)
+        {
+            Some(goal) if stats.has_left_pitch(squad.players[goal].id) => goal,
+            _ => return,
+        };
+        let cover = squad
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !stats.has_left_pitch(p.id))
+            .max_by_key(|(_, p)| {
+                (
+                    p.get_familiarity(position::Position::Goalkeeper),
+                    p.goalkeeping,
+                )
+            })
+            .map(|(slot, _)| slot);
+        if let Some(slot) = cover {
+            log::debug!("{:?} {} goes in goal", side, squad.players[slot].name);
+            squad.players.swap(goal, slot);
+        }
+    }
+
+    /// swap a player on the pitch for one from the bench
+    /// return whether a replacement was found
+    fn substitute(
+        &mut self,
+        side: event::Side,
+        minute: u8,
+        player_id: u32,
+        reason: event::SubReason,
+        area: Option<position::Area>,
+    ) -> bool {
+        let squad = match side {
+            event::Side::Home => &mut self.home,
+            event::Side::Away => &mut self.away,
+        };
+        let slot = match squad.players.iter().position(|p| p.id == player_id) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let mut slots = squad.formation.slots();
+        let on = match substitution::pick_replacement(&squad.subs, slots[slot], area) {
+            Some(i) => squad.subs.remove(i),
+            None => return false,
+        };
+        // changes for another area of the pitch reshape the formation around the new player
+        if area.is_some() {
+            slots[slot] = on.position;
+            squad.formation = formation::Formation::Custom(slots);
+        }
+        let off = std::mem::replace(&mut squad.players[slot], on.clone());
+        log::debug!("{:?} substitution: {} -> {}", side, off.name, on.name);
+        let player_off = off.id;
+        self.substituted.push(off);
+        self.positions_mut(side).push((on.id, slots[slot]));
+        self.add_event(
+            minute,
+            Some(side),
+            event::EventKind::Substitution {
+                player_off,
+                player_on: on.id,
+                reason,
+            },
+        );
+        true
+    }
+
+    /// apply a change to the stats of a team, for the match and for the half being played
+    fn update_stats(&mut self, side: event::Side, update: impl Fn(&mut GameStats)) {
+        let stats = match side {
+            event::Side::Home => &mut self.home_stats,
+            event::Side::Away => &mut self.away_stats,
+        };
+        update(stats);
+        if let Some(half_stats) = stats.halves.last_mut() {
+            update(half_stats);
+        }
+    }
+
+    /// add an event to the timeline and the stats of the team it belongs to
+    fn add_event(&mut self, minute: u8, side: Option<event::Side>, kind: event::EventKind) {
+        let event = event::MatchEvent { minute, side, kind };
+        log::trace!("{:?}", event);
+        if let Some(side) = side {
+            let stats = match side {
+                event::Side::Home => &mut self.home_stats,
+                event::Side::Away => &mut self.away_stats,
+            };
+            stats.record(&event);
+            // the shootout is not part of any half
+            let in_play = !matches!(event.kind, event::EventKind::ShootoutKick { .. });
+            if let (true, Some(half_stats)) = (in_play, stats.halves.last_mut()) {
+                half_stats.record(&event);
+            }
+            // the player fouled is on the other team
+            if let event::EventKind::Foul { fouled_id, .. } = event.kind {
+                self.update_stats(side.opponent(), |stats| {
+                    let player = stats.get_player_stats_mut(fouled_id);
+                    player.fouled = player.fouled.saturating_add(1);
+                });
+            }
+        }
+        self.events.push(event);
+    }
+
+    /// return fouls of both teams, the resulting bookings, injuries and the set pieces they give away
+    /// fouls are (player id, fouls, recklessness) of the home and the away team
+    /// players sent off, and fouled players forced off injured, are added to `sent_off`
+    fn get_foul_events(
+        &self,
+        home_fouls: &[(u32, u8, f32)],
+        away_fouls: &[(u32, u8, f32)],
+        players: (&[player::Player], &[player::Player]),
+        sent_off: &mut Vec<(u32, u8)>,
+        start: u8,
+        length: u8,
+    ) -> Vec<event::MatchEvent> {
+        // (minute, fouling team, player id, recklessness) of every foul
+        let mut tackles = Vec::new();
+        for (side, fouls) in [
+            (event::Side::Home, home_fouls),
+            (event::Side::Away, away_fouls),
+        ] {
+            for &(player_id, count, recklessness) in fouls {
+                for _ in 0..count {
+                    let minute = self.get_minute(start, length);
+                    tackles.push((minute, side, player_id, recklessness));
+                }
+            }
+        }
+        // in order of play, so cards and injuries stop players from taking further part
+        tackles.sort_by_key(|&(minute, _, _, _)| minute);
+
+        let mut booked = self
+            .home_stats
+            .yellow_cards
+            .iter()
+            .chain(self.away_stats.yellow_cards.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let mut events = Vec::new();
+        for (minute, side, player_id, recklessness) in tackles {
+            if sent_off
+                .iter()
+                .any(|&(id, m)| id == player_id && m <= minute)
+            {
+                continue;
+            }
+            let (team_players, opp_players) = match side {
+                event::Side::Home => players,
+                event::Side::Away => (players.1, players.0),
+            };
+            let fouled = match self.pick_player(opp_players, sent_off, minute, |p| {
+                (p.position != position::Position::Goalkeeper) as u8 as f32
+            }) {
+                Some(p) => p,
+                None => continue,
+            };
+            events.push(event::MatchEvent {
+                minute,
+                side: Some(side),
+                kind: event::EventKind::Foul {
+                    player_id,
+                    fouled_id: fouled.id,
+                },
+            });
+            // a second yellow is a red
+            let card = self.get_card(recklessness);
+            let cards = match card {
+                Some(event::Card::Yellow) if booked.contains(&player_id) => vec![
+                    (event::Card::Yellow, event::CardReason::Foul),
+                    (event::Card::Red, event::CardReason::SecondYellow),
+                ],
+                Some(event::Card::Yellow) => vec![(event::Card::Yellow, event::CardReason::Foul)],
+                Some(event::Card::Red) => {
+                    vec![(event::Card::Red, event::CardReason::SeriousFoulPlay)]
+                }
+                None => Vec::new(),
+            };
+            for (card, reason) in cards {
+                match card {
+                    event::Card::Yellow => booked.push(player_id),
+                    event::Card::Red => sent_off.push((player_id, minute)),
+                }
+                events.push(event::MatchEvent {
+                    minute,
+                    side: Some(side),
+                    kind: event::EventKind::Card {
+                        player_id,
+                        card,
+                        reason,
+                    },
+                });
+            }
+            // reckless challenges from clumsy tacklers hurt the most
+            if let Some(fouler) = team_players.iter().find(|p| p.id == player_id) {
+                let chance = self.get_foul_injury_chance(self.squad(side), fouler, card);
+                if let Some(kind) = self.get_injury(fouled, chance) {
+                    if let event::EventKind::Injury { severity, .. } = kind {
+                        if severity.forces_off() {
+                            sent_off.push((fouled.id, minute));
+                        }
+                    }
+                    events.push(event::MatchEvent {
+                        minute,
+                        side: Some(side.opponent()),
+                        kind,
+                    });
+                }
+            }
+            // set piece for the fouled team
+            let taker = match self.get_set_piece_taker(opp_players, sent_off, minute) {
+                Some(p) => p.id,
+                None => continue,
+            };
+            let mut rng = self.rng.write().unwrap();
+            // most fouls end with nothing more than a restart
+            let penalty_chance = rng.gen_range(0.005..0.05);
+            let kind = if rng.gen_bool(penalty_chance) {
+                event::EventKind::Penalty { player_id: taker }
+            } else if card.is_some() || rng.gen_bool(0.25) {
+                event::EventKind::FreeKick { player_id: taker }
+            } else {
+                continue;
+            };
+            events.push(event::MatchEvent {
+                minute,
+                side: Some(side.opponent()),
+                kind,
+            });
+        }
+        events
+    }
+
+    /// return the card a foul deserves, the more reckless the player the likelier a card
+    fn get_card(&self, recklessness: f32) -> Option<event::Card> {
+        let mut rng = self.rng.write().unwrap();
+        if rng.gen_bool(probability(recklessness as f64 * 0.004)) {
+            Some(event::Card::Red)
+        } else if rng.gen_bool(probability(recklessness as f64 * 0.15)) {
+            Some(event::Card::Yellow)
+        } else {
+            None
+        }
+    }
+
+    /// return injuries tired players of the team pick up over the spell without a challenge
+    /// players forced off are added to `sent_off`
+    fn get_fatigue_injuries(
+        &self,
+        side: event::Side,
+        players: &[player::Player],
+        sent_off: &mut Vec<(u32, u8)>,
+        start: u8,
+        length: u8,
+    ) -> Vec<event::MatchEvent> {
+        let scale = length as f64 / (HALF_LENGTH * 2) as f64;
+        let mut events = Vec::new();
+        for p in players {
+            if sent_off.iter().any(|&(id, _)| id == p.id) {
+                continue;
+            }
+            let tiredness = 1.0 - self.get_condition_factor(p) as f64;
+            let chance = tiredness * tiredness * 0.4 * scale;
+            if let Some(kind) = self.get_injury(p, chance) {
+                let minute = self.get_minute(start, length);
+                if let event::EventKind::Injury { severity, .. } = kind {
+                    if severity.forces_off() {
+                        sent_off.push((p.id, minute));
+                    }
+                }
+                events.push(event::MatchEvent {
+                    minute,
+                    side: Some(side),
+                    kind,
+                });
+            }
+        }
+        events
+    }
+
+    /// return chance of a foul injuring the fouled player
+    /// calculated based on:
+    /// - tactics: aggression
+    /// - tackling of the fouling player
+    /// - card shown for the foul
+    fn get_foul_injury_chance(
+        &self,
+        team: &squad::Squad,
+        player: &player::Player,
+        card: Option<event::Card>,
+    ) -> f64 {
+        let max = u8::MAX as f64;
+        let mut chance = 0.005
+            + team.tactics.aggression as f64 / max * 0.01
+            + (max - player.tackling as f64) / max * 0.01;
+        chance += match card {
+            Some(event::Card::Yellow) => 0.02,
+            Some(event::Card::Red) => 0.1,
+            None => 0.0,
+        };
+        chance
+    }
+
+    /// roll whether the player picks up an injury, returning the injury event
+    /// most injuries are knocks, serious ones are rare
+    fn get_injury(&self, player: &player::Player, chance: f64) -> Option<event::EventKind> {
+        let mut rng = self.rng.write().unwrap();
+        if !rng.gen_bool(probability(chance)) {
+            return None;
+        }
+        let severity = match rng.gen_range(0.0..1.0) {
+            r if r < 0.5 => injury::Severity::Knock,
+            r if r < 0.8 => injury::Severity::Minor,
+            r if r < 0.95 => injury::Severity::Moderate,
+            _ => injury::Severity::Serious,
+        };
+        Some(event::EventKind::Injury {
+            player_id: player.id,
+            severity,
+            days_out: rng.gen_range(severity.days_out()),
+        })
+    }
+
+    /// return crosses of the attacking team and the shots they are about to take
+    /// counts are (crosses, shots) expected over the spell
+    #[allow(clippy::too_many_arguments)]
+    fn get_attack_events<'p>(
+        &self,
+        side: event::Side,
+        players: &'p [player::Player],
+        opp_players: &'p [player::Player],
+        counts: (f32, f32),
+        header_chance: f64,
+        sent_off: &[(u32, u8)],
+        start: u8,
+        length: u8,
+    ) -> (Vec<event::MatchEvent>, Vec<Attempt<'p>>) {
+        let (crosses, shots) = {
+            let mut rng = self.rng.write().unwrap();
+            (
+                sample_count(&mut *rng, counts.0),
+                sample_count(&mut *rng, counts.1),
+            )
+        };
+        let mut events = Vec::new();
+        let mut attempts = Vec::new();
+        for _ in 0..crosses {
+            let minute = self.get_minute(start, length);
+            let crosser = match self.pick_player(players, sent_off, minute, |p| {
+                let multiplier = match p.position {
+                    position::Position::LeftWing | position::Position::RightWing => 1.0,
+                    position::Position::LeftMidfield | position::Position::RightMidfield => 0.9,
+                    position::Position::LeftWingBack
+                    | position::Position::RightWingBack
+                    | position::Position::LeftBack
+                    | position::Position::RightBack => 0.7,
+                    position::Position::Goalkeeper => 0.0,
+                    _ => 0.1,
+                };
+                p.passing as f32 * multiplier
+            }) {
+                Some(p) => p,
+                None => continue,
+            };
+            events.push(event::MatchEvent {
+                minute,
+                side: Some(side),
+                kind: event::EventKind::Cross {
+                    player_id: crosser.id,
+                },
+            });
+            // aerial duel in the box
+            let (header, own_goal) = {
+                let mut rng = self.rng.write().unwrap();
+                let header = rng.gen_bool(probability(header_chance));
+                (header, !header && rng.gen_bool(OWN_GOAL_CHANCE))
+            };
+            if own_goal {
+                // defender turns the cross into their own net
+                if let Some(p) =
+                    self.pick_player(opp_players, sent_off, minute, |p| match p.position {
+                        position::Position::CenterBack => 1.0,
+                        position::Position::LeftBack
+                        | position::Position::RightBack
+                        | position::Position::LeftWingBack
+                        | position::Position::RightWingBack => 0.6,
+                        position::Position::DefensiveMidfield => 0.3,
+                        _ => 0.05,
+                    })
+                {
+                    events.push(event::MatchEvent {
+                        minute,
+                        side: Some(side),
+                        kind: event::EventKind::Goal {
+                            player_id: p.id,
+                            assist_id: None,
+                            goal_type: event::GoalType::OwnGoal,
+                        },
+                    });
+                }
+                continue;
+            }
+            if !header {
+                continue;
+            }
+            if let Some(p) = self.pick_player(players, sent_off, minute, |p| {
+                if p.id == crosser.id {
+                    return 0.0;
+                }
+                let multiplier = match p.position {
+                    position::Position::Striker => 1.0,
+                    position::Position::RightWing | position::Position::LeftWing => 0.6,
+                    position::Position::AttackingMidfield
+                    | position::Position::LeftMidfield
+                    | position::Position::RightMidfield => 0.3,
+                    position::Position::CenterMidfield => 0.2,
+                    position::Position::Goalkeeper => 0.0,
+                    _ => 0.1,
+                };
+                (p.heading as f32 + p.jumping as f32 * 0.5 + p.attack_positioning as f32 * 0.5)
+                    * multiplier
+            }) {
+                attempts.push(Attempt {
+                    minute,
+                    shooter: p,
+                    assister: Some(crosser),
+                    goal_type: event::GoalType::Header,
+                    origin: event::ShotOrigin::Header,
+                });
+            }
+        }
+        for _ in 0..shots {
+            let minute = self.get_minute(start, length);
+            let shooter = match self.pick_player(players, sent_off, minute, |p| {
+                let multiplier = match p.position {
+                    position::Position::Striker => 1.0,
+                    position::Position::LeftWing | position::Position::RightWing => 0.8,
+                    position::Position::LeftMidfield
+                    | position::Position::AttackingMidfield
+                    | position::Position::RightMidfield => 0.7,
+                    position::Position::CenterMidfield => 0.6,
+                    position::Position::Goalkeeper => 0.0,
+                    _ => 0.2,
+                };
+                (p.shooting as f32 * 0.6 + p.attack_positioning as f32 * 0.4) * multiplier
+            }) {
+                Some(p) => p,
+                None => continue,
+            };
+            // most chances are created by a team mate
+            let (assisted, long_shot) = {
+                let mut rng = self.rng.write().unwrap();
+                (
+                    rng.gen_bool(ASSIST_CHANCE),
+                    rng.gen_bool(probability(self.get_long_shot_chance(shooter))),
+                )
+            };
+            let assister = match assisted {
+                true => self.pick_player(players, sent_off, minute, |p| {
+                    if p.id == shooter.id {
+                        return 0.0;
+                    }
+                    let multiplier = match p.position {
+                        position::Position::AttackingMidfield => 1.0,
+                        position::Position::LeftWing | position::Position::RightWing => 0.9,
+                        position::Position::CenterMidfield
+                        | position::Position::LeftMidfield
+                        | position::Position::RightMidfield
+                        | position::Position::Striker => 0.7,
+                        position::Position::LeftWingBack | position::Position::RightWingBack => 0.5,
+                        position::Position::DefensiveMidfield => 0.4,
+                        position::Position::Goalkeeper => 0.01,
+                        _ => 0.2,
+                    };
+                    (p.passing as f32 * 0.5 + p.vision as f32 * 0.5) * multiplier
+                }),
+                false => None,
+            };
+            attempts.push(Attempt {
+                minute,
+                shooter,
+                assister,
+                goal_type: event::GoalType::OpenPlay,
+                origin: match long_shot {
+                    true => event::ShotOrigin::LongShot,
+                    false => event::ShotOrigin::OpenPlay,
+                },
+            });
+        }
+        (events, attempts)
+    }
+
+    /// return shots from penalties and direct free kicks the team was awarded
+    fn get_set_piece_attempts<'p>(
+        &self,
+        side: event::Side,
+        players: &'p [player::Player],
+        events: &[event::MatchEvent],
+    ) -> Vec<Attempt<'p>> {
+        let mut attempts = Vec::new();
+        for e in events.iter().filter(|e| e.side == Some(side)) {
+            let (taker_id, goal_type, origin) = match e.kind {
+                event::EventKind::Penalty { player_id } => (
+                    player_id,
+                    event::GoalType::Penalty,
+                    event::ShotOrigin::Penalty,
+                ),
+                event::EventKind::FreeKick { player_id } => (
+                    player_id,
+                    event::GoalType::DirectFreeKick,
+                    event::ShotOrigin::FreeKick,
+                ),
+                _ => continue,
+            };
+            let taker = match players.iter().find(|p| p.id == taker_id) {
+                Some(p) => p,
+                None => continue,
+            };
+            // only some free kicks are close enough to shoot from
+            if goal_type == event::GoalType::DirectFreeKick {
+                let direct_chance =
+                    0.1 + (taker.set_pieces as f64 + taker.long_shots as f64) / 510.0 * 0.2;
+                if !self
+                    .rng
+                    .write()
+                    .unwrap()
+                    .gen_bool(probability(direct_chance))
+                {
+                    continue;
+                }
+            }
+            attempts.push(Attempt {
+                minute: e.minute,
+                shooter: taker,
+                assister: None,
+                goal_type,
+                origin,
+            });
+        }
+        attempts
+    }
 
-impl<'a> Game<'a> {
-    pub fn new(home_squad: squad::Squad<'a>, away_squad: squad::Squad<'a>) -> Self {
-        Self {
-            home: home_squad,
-            away: away_squad,
-            home_stats: GameStats::default(),
-            away_stats: GameStats::default(),
-            rng: Arc::new(RwLock::new(thread_rng())),
+    /// return shots of the attacking team and how each shot on target ends
+    /// `players` are (attacking team, defending team)
+    fn get_shot_events(
+        &self,
+        side: event::Side,
+        (players, opp_players): (&[player::Player], &[player::Player]),
+        opp_def_str: f32,
+        attempts: Vec<Attempt>,
+        sent_off: &[(u32, u8)],
+    ) -> Vec<event::MatchEvent> {
+        let xg = attempts
+            .iter()
+            .map(|attempt| self.get_xg(attempt, opp_def_str))
+            .collect::<Vec<_>>();
+        // based on shots calculate chances of hitting the target
+        let on_target = self.get_shots_on_target(self.squad(side), players, &attempts);
+        // based on xG calculate goals
+        // opponent keeper still on the pitch when each shot is taken
+        let keepers = attempts
+            .iter()
+            .map(|attempt| {
+                opp_players.iter().find(|p| {
+                    p.position == position::Position::Goalkeeper
+                        && !sent_off
+                            .iter()
+                            .any(|&(id, m)| id == p.id && m <= attempt.minute)
+                })
+            })
+            .collect::<Vec<_>>();
+        let outcomes = self.get_goals(&keepers, &attempts, &xg, &on_target);
+
+        let mut events = Vec::new();
+        for (((attempt, (on_target, goal)), keeper), xg) in
+            attempts.into_iter().zip(outcomes).zip(keepers).zip(xg)
+        {
+            events.push(event::MatchEvent {
+                minute: attempt.minute,
+                side: Some(side),
+                kind: event::EventKind::Shot {
+                    player_id: attempt.shooter.id,
+                    assist_id: attempt.assister.map(|p| p.id),
+                    on_target,
+                    origin: attempt.origin,
+                    xg,
+                },
+            });
+            if !on_target {
+                continue;
+            }
+            match (goal, keeper) {
+                (false, Some(keeper)) => events.push(event::MatchEvent {
+                    minute: attempt.minute,
+                    side: Some(side.opponent()),
+                    kind: event::EventKind::Save {
+                        keeper_id: keeper.id,
+                        shooter_id: attempt.shooter.id,
+                    },
+                }),
+                _ => events.push(event::MatchEvent {
+                    minute: attempt.minute,
+                    side: Some(side),
+                    kind: event::EventKind::Goal {
+                        player_id: attempt.shooter.id,
+                        assist_id: attempt.assister.map(|p| p.id),
+                        goal_type: attempt.goal_type,
+                    },
+                }),
+            }
         }
+        events
     }
 
-    pub fn play(&mut self) {
-        self.play_half();
+    /// return corners won by the team, taken by their set piece taker
+    fn get_corner_events(
+        &self,
+        side: event::Side,
+        players: &[player::Player],
+        corners: f32,
+        sent_off: &[(u32, u8)],
+        start: u8,
+        length: u8,
+    ) -> Vec<event::MatchEvent> {
+        let corners = sample_count(&mut *self.rng.write().unwrap(), corners);
+        let mut events = Vec::new();
+        for _ in 0..corners {
+            let minute = self.get_minute(start, length);
+            if let Some(taker) = self.get_set_piece_taker(players, sent_off, minute) {
+                events.push(event::MatchEvent {
+                    minute,
+                    side: Some(side),
+                    kind: event::EventKind::Corner {
+                        player_id: taker.id,
+                    },
+                });
+            }
+        }
+        events
     }
 
-    pub fn get_home_stats(&self) -> GameStats {
-        self.home_stats.to_owned()
+    /// return tackles won by the defending team, each off an outfield opponent
+    /// `players` are (defending team, attacking team)
+    fn get_tackle_events(
+        &self,
+        side: event::Side,
+        (players, opp_players): (&[player::Player], &[player::Player]),
+        tackles: f32,
+        sent_off: &[(u32, u8)],
+        start: u8,
+        length: u8,
+    ) -> Vec<event::MatchEvent> {
+        let tackles = sample_count(&mut *self.rng.write().unwrap(), tackles);
+        let mut events = Vec::new();
+        for _ in 0..tackles {
+            let minute = self.get_minute(start, length);
+            let tackler = self.pick_player(players, sent_off, minute, |p| {
+                get_tackling(p) * get_tackle_multiplier(p.position)
+            });
+            let tackled = self.pick_player(opp_players, sent_off, minute, |p| {
+                (p.position != position::Position::Goalkeeper) as u8 as f32
+            });
+            if let (Some(tackler), Some(tackled)) = (tackler, tackled) {
+                events.push(event::MatchEvent {
+                    minute,
+                    side: Some(side),
+                    kind: event::EventKind::Tackle {
+                        player_id: tackler.id,
+                        tackled_id: tackled.id,
+                    },
+                });
+            }
+        }
+        events
     }
-    pub fn get_away_stats(&self) -> GameStats {
-        self.away_stats.to_owned()
+
+    /// return passes of the team during the spell as (player id, attempted, completed) of each
+    /// passer
+    /// opponents who win a pass that goes astray are added to `interceptions`, once for each pass
+    #[allow(clippy::too_many_arguments)]
+    fn get_passing(
+        &self,
+        (team, opp_team): (&squad::Squad, &squad::Squad),
+        (players, opp_players): (&[player::Player], &[player::Player]),
+        passes: f32,
+        interceptions: &mut Vec<u32>,
+        sent_off: &[(u32, u8)],
+        start: u8,
+        length: u8,
+    ) -> Vec<(u32, u16, u16)> {
+        let passes = sample_count(&mut *self.rng.write().unwrap(), passes);
+        let mut passing: Vec<(u32, u16, u16)> = Vec::new();
+        for _ in 0..passes {
+            let minute = self.get_minute(start, length);
+            let passer = match self.pick_player(players, sent_off, minute, |p| {
+                (p.passing as f32 * 0.6 + p.vision as f32 * 0.4) * get_pass_multiplier(p.position)
+            }) {
+                Some(passer) => passer,
+                None => continue,
+            };
+            let chance = self.get_pass_completion(team, opp_team, passer);
+            let completed = self.rng.write().unwrap().gen_bool(probability(chance));
+            match passing.iter_mut().find(|(id, _, _)| *id == passer.id) {
+                Some((_, attempted, done)) => {
+                    *attempted += 1;
+                    *done += completed as u16;
+                }
+                None => passing.push((passer.id, 1, completed as u16)),
+            }
+            if completed || !self.rng.write().unwrap().gen_bool(INTERCEPTION_CHANCE) {
+                continue;
+            }
+            let interceptor = self.pick_player(opp_players, sent_off, minute, |p| {
+                (p.defensive_positioning as f32 * 0.6 + p.decision_making as f32 * 0.4)
+                    * get_tackle_multiplier(p.position)
+            });
+            interceptions.extend(interceptor.map(|p| p.id));
+        }
+        passing
     }
 
-    fn play_half(&mut self) {
-        let (mut home_stats, mut away_stats) = (GameStats::default(), GameStats::default());
-        // carry over stats
-        {
-            // bookings
-            home_stats.yellow_cards = self.home_stats.yellow_cards.clone();
-            away_stats.yellow_cards = self.away_stats.yellow_cards.clone();
-            home_stats.red_cards = self.home_stats.red_cards.clone();
-            away_stats.red_cards = self.away_stats.red_cards.clone();
-            // goals
-            home_stats.goals = self.home_stats.goals;
-            away_stats.goals = self.away_stats.goals;
+    /// return chance of a pass finding a team mate
+    /// calculated based on:
+    /// - player passing, vision
+    /// - tactics: pass_range, longer balls go astray more often
+    /// - opponent tactics: defense_line, a high press closes passers down
+    fn get_pass_completion(
+        &self,
+        team: &squad::Squad,
+        opp_team: &squad::Squad,
+        player: &player::Player,
+    ) -> f64 {
+        let max = u8::MAX as f64;
+        let skill = (player.passing as f64 * 0.7 + player.vision as f64 * 0.3) / max;
+        0.65 + skill * 0.35
+            - team.tactics.pass_range as f64 / max * 0.15
+            - opp_team.tactics.defense_line as f64 / max * 0.05
+    }
+
+    /// return (player id, minutes, km covered) of each player of the team on the pitch during
+    /// the spell, up to the minute they went off if sent off or forced off injured
+    fn get_time_played(
+        &self,
+        team: &squad::Squad,
+        players: &[player::Player],
+        sent_off: &[(u32, u8)],
+        start: u8,
+        length: u8,
+    ) -> Vec<(u32, u8, f32)> {
+        players
+            .iter()
+            .map(|p| {
+                let minutes = sent_off
+                    .iter()
+                    .find(|&&(id, _)| id == p.id)
+                    .map_or(length, |&(_, m)| m.saturating_sub(start).min(length));
+                let distance = self.get_distance(team, p) * minutes as f32 / 90.0;
+                (p.id, minutes, distance)
+            })
+            .collect()
+    }
+
+    /// return km a player covers over 90 minutes
+    /// calculated based on:
+    /// - position played: keepers stay in their box
+    /// - player stamina, pace, worn down by their condition
+    /// - tactics: defense_line, build_up_speed
+    /// - playstyle
+    fn get_distance(&self, team: &squad::Squad, player: &player::Player) -> f32 {
+        let max = u8::MAX as f32;
+        if player.position == position::Position::Goalkeeper {
+            return 4.5 + player.stamina as f32 / max;
         }
-        // get players
-        let mut home_players = self
-            .get_players(&self.home, home_stats.clone())
-            .collect::<Vec<_>>();
-        let mut away_players = self
-            .get_players(&self.away, away_stats.clone())
+        let mut distance = 9.0 + player.stamina as f32 / max * 2.0 + player.pace as f32 / max * 0.5;
+        distance *= 1.0
+            + (team.tactics.defense_line as f32 / max - 0.5) * 0.1
+            + (team.tactics.build_up_speed as f32 / max - 0.5) * 0.05;
+        distance *= match player.playstyle {
+            style::PlayStyle::BoxToBox => 1.1,
+            style::PlayStyle::Wide | style::PlayStyle::TrackBack => 1.05,
+            _ => 1.0,
+        };
+        distance
+    }
+
+    /// return a random minute within the spell
+    fn get_minute(&self, start: u8, length: u8) -> u8 {
+        self.rng
+            .write()
+            .unwrap()
+            .gen_range(start + 1..=start + length.max(1))
+    }
+
+    /// pick a random player, with chances proportional to `weight`
+    /// players sent off by `minute` are not picked
+    fn pick_player<'p>(
+        &self,
+        players: &'p [player::Player],
+        sent_off: &[(u32, u8)],
+        minute: u8,
+        weight: impl Fn(&player::Player) -> f32,
+    ) -> Option<&'p player::Player> {
+        let weights = players
+            .iter()
+            .map(|p| {
+                if sent_off.iter().any(|&(id, m)| id == p.id && m <= minute) {
+                    0.0
+                } else {
+                    weight(p).max(0.0)
+                }
+            })
             .collect::<Vec<_>>();
-        // --- calculations ---
-        // calculate possession of each team
-        let (home_poss, away_poss) =
-            self.get_possession(&self.home, &self.away, &home_stats, &away_stats);
-        {
-            // modify stats
-            home_stats.possession = home_poss;
-            away_stats.possession = away_poss;
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
         }
-        // calculate fouls based on possession
-        // based on fouls calculate freekicks and yellow cards and red cards
-        let (home_fouls, home_yellows, home_reds) = self.get_fouls(&self.home, &home_stats);
-        let (away_fouls, away_yellows, away_reds) = self.get_fouls(&self.away, &away_stats);
-        {
-            // modify stats
-            home_stats.fouls += home_fouls;
-            home_stats.yellow_cards.extend(home_yellows);
-            home_stats.red_cards.extend(home_reds.to_owned());
-            away_stats.fouls += away_fouls;
-            away_stats.yellow_cards.extend(away_yellows);
-            away_stats.red_cards.extend(away_reds.to_owned());
+        let mut roll = self.rng.write().unwrap().gen_range(0.0..total);
+        for (p, w) in players.iter().zip(weights) {
+            if roll < w {
+                return Some(p);
+            }
+            roll -= w;
         }
+        players.last()
+    }
 
-        // modify posession based on red cards
-        {
-            let red_cards_diff =
-                home_stats.red_cards.len() as i32 - away_stats.red_cards.len() as i32;
-            if red_cards_diff > 0 {
-                // home has more red cards
-                home_stats.possession =
-                    f32::powi(0.65, red_cards_diff) as f32 * home_stats.possession;
-                away_stats.possession = 1.0 - home_stats.possession;
-            } else {
-                // away has more red cards
-                if away_reds.len() > 0 {
-                    away_stats.possession =
-                        f32::powi(0.25, red_cards_diff * -1) as f32 * away_stats.possession;
-                    home_stats.possession = 1.0 - away_stats.possession;
-                }
-            };
-            // update players
+    /// return the outfield player with the best set pieces still on the pitch at `minute`
+    fn get_set_piece_taker<'p>(
+        &self,
+        players: &'p [player::Player],
+        sent_off: &[(u32, u8)],
+        minute: u8,
+    ) -> Option<&'p player::Player> {
+        players
+            .iter()
+            .filter(|p| p.position != position::Position::Goalkeeper)
+            .filter(|p| !sent_off.iter().any(|&(id, m)| id == p.id && m <= minute))
+            .max_by_key(|p| p.set_pieces)
+    }
 
-            // get players
-            home_players = self
-                .get_players(&self.home, home_stats.clone())
-                .collect::<Vec<_>>();
-            away_players = self
-                .get_players(&self.away, away_stats.clone())
-                .collect::<Vec<_>>();
+    fn squad(&self, side: event::Side) -> &squad::Squad {
+        match side {
+            event::Side::Home => &self.home,
+            event::Side::Away => &self.away,
         }
+    }
 
-        // --- get squad strength --
-        let home_def = self.get_squad_def_strength(&self.home, &home_stats);
-        let away_def = self.get_squad_def_strength(&self.away, &away_stats);
-        // gerial threat , defense
-        let home_aerial_threat = self.get_atk_aerial(home_players.clone().into_iter());
-        let home_aerial_def = self.get_def_aerial(home_players.clone().into_iter());
-        let away_aerial_threat = self.get_atk_aerial(away_players.clone().into_iter());
-        let away_aerial_def = self.get_def_aerial(away_players.clone().into_iter());
-        let home_wide_atk = self.get_wide_atk(&self.home, home_players.clone().into_iter());
-        let away_wide_atk = self.get_wide_atk(&self.away, away_players.clone().into_iter());
-        let home_wide_def = self.get_wide_def(&self.home, home_players.clone().into_iter());
-        let away_wide_def = self.get_wide_def(&self.home, away_players.clone().into_iter());
-
-        // based on possession get crosses
-        let home_crosses = self.get_crosses(&self.home, &home_stats, home_wide_atk, away_wide_def);
-        let away_crosses = self.get_crosses(&self.away, &away_stats, away_wide_atk, home_wide_def);
-        {
-            // modify stats
-            home_stats.crosses += home_crosses;
-            away_stats.crosses += away_crosses;
-        }
-        // based on possession and tactics calculate shots
-        let home_shots = self.get_shots(
-            &self.home,
-            home_players.clone().into_iter(),
-            &home_stats,
-            home_aerial_threat,
-            away_aerial_def,
-            away_def,
-        );
-        let away_shots = self.get_shots(
-            &self.away,
-            away_players.clone().into_iter(),
-            &away_stats,
-            away_aerial_threat,
-            home_aerial_def,
-            home_def,
-        );
-        {
-            // modify stats
-            home_stats.shots += home_shots;
-            away_stats.shots += away_shots;
+    fn stats(&self, side: event::Side) -> &GameStats {
+        match side {
+            event::Side::Home => &self.home_stats,
+            event::Side::Away => &self.away_stats,
         }
-        // calculate setpieces: corners, freekicks, penalties(based on fouls)
-        let (home_ck, home_fk, home_pn) = self.get_set_pieces(&self.home, &home_stats, &away_stats);
-        let (away_ck, away_fk, away_pn) = self.get_set_pieces(&self.away, &away_stats, &home_stats);
-        {
-            // modify stats
-            home_stats.corners += home_ck;
-            home_stats.freekicks += home_fk;
-            home_stats.penalties += home_pn;
-            away_stats.corners += away_ck;
-            away_stats.freekicks += away_fk;
-            away_stats.penalties += away_pn;
-        }
-        // based on shots and corners and freekicks calculate shots on target
-        let home_sot =
-            self.get_shots_on_target(&self.home, home_players.clone().into_iter(), &home_stats);
-        let away_sot =
-            self.get_shots_on_target(&self.away, away_players.clone().into_iter(), &away_stats);
-        {
-            // modify stats
-            home_stats.shots_on_target += home_sot;
-            away_stats.shots_on_target += away_sot;
+    }
+
+    fn positions_mut(&mut self, side: event::Side) -> &mut Vec<(u32, position::Position)> {
+        match side {
+            event::Side::Home => &mut self.home_positions,
+            event::Side::Away => &mut self.away_positions,
         }
-        // based on shots on target calculate goals
-        let home_goals = self.get_goals(&self.away, &home_stats);
-        let away_goals = self.get_goals(&self.home, &away_stats);
-        {
-            // modify stats
-            home_stats.goals += home_goals;
-            away_stats.goals += away_goals;
+    }
+
+    /// return players of the team still on the pitch in the position of their slot,
+    /// held back by how unfamiliar they are with it and worn down by their condition
+    /// players sent off or forced off injured without a replacement leave the team short
+    fn get_players(&self, side: event::Side) -> Vec<player::Player> {
+        let stats = self.stats(side);
+        self.squad(side)
+            .get_lineup()
+            .filter(|(_, p)| !stats.has_left_pitch(p.id))
+            .map(|(slot, p)| {
+                p.in_position(slot)
+                    .with_condition(self.get_condition_factor(p))
+            })
+            .collect()
+    }
+
+    /// drain condition of the players of the team still on the pitch after playing `minutes`
+    fn drain_condition(&mut self, side: event::Side, minutes: u8) {
+        let team = self.squad(side);
+        let stats = self.stats(side);
+        let drains = team
+            .get_lineup()
+            .filter(|(_, p)| !stats.has_left_pitch(p.id))
+            .map(|(slot, p)| (p.id, self.get_drain(team, p, slot) * minutes as f32))
+            .collect::<Vec<_>>();
+        for (id, drain) in drains {
+            if let Some(condition) = self.condition.get_mut(&id) {
+                *condition = (*condition - drain).max(0.0);
+            }
         }
-        // add game_half stats back to game stats
-        {
-            self.home_stats.possession = home_stats.possession;
-            self.home_stats.shots += home_stats.shots;
-            self.home_stats.shots_on_target += home_stats.shots_on_target;
-            self.home_stats.goals += home_stats.goals;
-            self.home_stats.freekicks += home_stats.freekicks;
-            self.home_stats.penalties += home_stats.penalties;
-            self.home_stats.corners += home_stats.corners;
-            self.home_stats.fouls += home_stats.fouls;
-            self.home_stats.yellow_cards.extend(home_stats.yellow_cards);
-            self.home_stats.red_cards.extend(home_stats.red_cards);
-
-            self.away_stats.possession = away_stats.possession;
-            self.away_stats.shots += away_stats.shots;
-            self.away_stats.shots_on_target += away_stats.shots_on_target;
-            self.away_stats.goals += away_stats.goals;
-            self.away_stats.freekicks += away_stats.freekicks;
-            self.away_stats.penalties += away_stats.penalties;
-            self.away_stats.corners += away_stats.corners;
-            self.away_stats.fouls += away_stats.fouls;
-            self.away_stats.yellow_cards.extend(away_stats.yellow_cards);
-            self.away_stats.red_cards.extend(away_stats.red_cards);
+    }
+
+    /// return condition a player loses per minute
+    /// calculated based on:
+    /// - stamina, fitness
+    /// - tactics: defense_line, aggression, build_up_speed
+    /// - playstyle
+    /// - position played: keepers barely tire
+    fn get_drain(
+        &self,
+        team: &squad::Squad,
+        player: &player::Player,
+        position: position::Position,
+    ) -> f32 {
+        let max = u8::MAX as f32;
+        let mut drain = 0.12
+            + (max - player.stamina as f32) / max * 0.2
+            + (max - player.fitness as f32) / max * 0.08;
+        // pressing high, tackling hard and playing at tempo all take their toll
+        drain *= 1.0
+            + (team.tactics.defense_line as f32 / max - 0.5) * 0.3
+            + (team.tactics.aggression as f32 / max - 0.5) * 0.2
+            + (team.tactics.build_up_speed as f32 / max - 0.5) * 0.2;
+        drain *= match player.playstyle {
+            style::PlayStyle::BoxToBox => 1.25,
+            style::PlayStyle::Wide | style::PlayStyle::TrackBack => 1.15,
+            style::PlayStyle::CutInside | style::PlayStyle::False9 => 1.05,
+            _ => 1.0,
+        };
+        if position == position::Position::Goalkeeper {
+            drain *= 0.3;
         }
+        drain
+    }
+
+    /// return condition of a player as a factor (1.0 = fresh)
+    fn get_condition_factor(&self, player: &player::Player) -> f32 {
+        self.condition.get(&player.id).copied().unwrap_or(100.0) * 0.01
+    }
+
+    /// return attacking intent based on the scoreline
+    /// trailing teams commit more players forward, leading teams sit deeper
+    fn get_scoreline_intent(&self, stats: &GameStats, opp_stats: &GameStats) -> f32 {
+        let diff = stats.goals as f32 - opp_stats.goals as f32;
+        (1.0 - diff * 0.08).clamp(0.75, 1.3)
     }
 
     /// return posession for each team
@@ -234,85 +2198,132 @@ impl<'a> Game<'a> {
     ///     - morale
     ///     - form
     ///     - stamina
+    /// - players sent off or injured
+    /// - scoreline
+    ///
     /// first value is home team, second value is away team
     fn get_possession(
         &self,
         home_team: &squad::Squad,
         away_team: &squad::Squad,
+        home_players: &[player::Player],
+        away_players: &[player::Player],
         home_stats: &GameStats,
         away_stats: &GameStats,
     ) -> (f32, f32) {
         // (tactics + formation + player playstyle) * tactics success rate * quality of players * home adv
         // home team
-        let home_score = self.get_team_poss_score(home_team, home_stats) * 1.1;
-        let away_score = self.get_team_poss_score(away_team, away_stats);
+        let home_score = self.get_team_poss_score(home_team, home_players)
+            * 1.1
+            * self.get_scoreline_intent(home_stats, away_stats);
+        let away_score = self.get_team_poss_score(away_team, away_players)
+            * self.get_scoreline_intent(away_stats, home_stats);
 
         let total = home_score + away_score;
+        let (mut home_poss, mut away_poss) = match total > 0.0 && total.is_finite() {
+            true => (home_score / total, away_score / total),
+            false => (0.5, 0.5),
+        };
 
-        (home_score / total, away_score / total)
+        // modify posession based on players sent off or injured
+        let short_diff = away_players.len() as i32 - home_players.len() as i32;
+        if short_diff > 0 {
+            // home is down to fewer players
+            home_poss *= f32::powi(0.65, short_diff);
+            away_poss = 1.0 - home_poss;
+        } else if short_diff < 0 {
+            // away is down to fewer players
+            away_poss *= f32::powi(0.65, -short_diff);
+            home_poss = 1.0 - away_poss;
+        };
+        (home_poss, away_poss)
     }
 
-    /// return fouls, yellow and red cards for each team
+    /// return fouls of each player over a spell as (player id, fouls, recklessness)
     /// calculated based on:
     /// - stamina
     /// - decision making
     /// - tactics
     /// - possession
-    /// - existing cards
+    /// - existing cards: booked players hold back
     ///
-    fn get_fouls(&self, team: &squad::Squad, stats: &GameStats) -> (u8, Vec<u32>, Vec<u32>) {
+    /// `scale` is the length of the spell relative to a match
+    fn get_fouls(
+        &self,
+        team: &squad::Squad,
+        players: &[player::Player],
+        stats: &GameStats,
+        scale: f32,
+    ) -> Vec<(u32, u8, f32)> {
         let mut rng = self.rng.write().unwrap();
-        let mut fouls: f32 = 0.0;
-        let mut yellow_cards: Vec<u32> = Vec::new();
-        let mut red_cards: Vec<u32> = Vec::new();
-        for &player in team
-            .players
-            .iter()
-            .filter(|p| !stats.red_cards.contains(&p.id))
-        {
+        let mut fouls = Vec::new();
+        for player in players {
             if player.position == position::Position::Goalkeeper {
                 continue;
             }
             // less stamina = more easily tired = more chance to commit a foul
             let mut player_foul: f32 = 0.1;
-            player_foul += u8::MAX as f32 / player.stamina as f32 * 0.1;
-            player_foul += u8::MAX as f32 / player.decision_making as f32 * 0.4;
-            player_foul += team.tactics.aggression as f32 / player.tackling as f32 * 0.1;
-            // yellow_card rate
-            let mut yellows = 0;
-            if rng.gen_bool((player_foul * 0.25) as f64) {
-                yellow_cards.push(player.id);
-                yellows += 1;
-            };
-            // red card rate
-            let mut reds = 0;
-            if rng.gen_bool((player_foul * 0.005) as f64) {
-                red_cards.push(player.id);
-                reds += 1;
-            };
+            player_foul += u8::MAX as f32 / player.stamina.max(1) as f32 * 0.1;
+            player_foul += u8::MAX as f32 / player.decision_making.max(1) as f32 * 0.4;
+            player_foul += team.tactics.aggression as f32 / player.tackling.max(1) as f32 * 0.1;
 
             // foul rate
-            let cards = yellows + reds;
-            fouls += rng.gen_range(cards as f32..(cards as f32 + player_foul * 2.5));
+            let mut player_fouls = rng.gen_range(0.0..player_foul * 5.0 * scale) * stats.possession;
+            if stats.yellow_cards.contains(&player.id) {
+                player_fouls *= 0.7;
+            }
+            let player_fouls = sample_count(&mut *rng, player_fouls);
+            if player_fouls > 0 {
+                fouls.push((player.id, player_fouls, player_foul));
+            }
         }
-        fouls *= stats.possession;
-        (fouls.round() as u8, yellow_cards, red_cards)
+        fouls
+    }
+
+    /// get number of tackles the team wins over a match
+    /// calculated based on:
+    /// - tactics: aggression
+    /// - player tackling, defensive positioning
+    /// - possession of the opponent
+    fn get_tackles(
+        &self,
+        team: &squad::Squad,
+        players: &[player::Player],
+        opp_stats: &GameStats,
+    ) -> f32 {
+        let tackling = players
+            .iter()
+            .map(|p| get_tackling(p) * get_tackle_multiplier(p.position))
+            .sum::<f32>()
+            / u8::MAX as f32;
+        let aggression = 0.5 + team.tactics.aggression as f32 / u8::MAX as f32;
+        tackling * aggression * opp_stats.possession * 8.0
+    }
+
+    /// get number of passes the team attempts over a match
+    /// calculated based on:
+    /// - possession
+    /// - tactics: pass_range, build_up_speed, going long or going quickly means fewer passes
+    fn get_passes(&self, team: &squad::Squad, stats: &GameStats) -> f32 {
+        let max = u8::MAX as f32;
+        let tempo = 1.0
+            - (team.tactics.pass_range as f32 / max - 0.5) * 0.4
+            - (team.tactics.build_up_speed as f32 / max - 0.5) * 0.2;
+        stats.possession * 1000.0 * tempo
     }
 
-    /// get number of shots for the team
+    /// get number of shots for the team over a match, headers from crosses not included
     /// calculated based on:
-    /// - tactics: shoot_more_often, cross_more_often
+    /// - tactics: shoot_more_often
     /// - player creativity, passing,technique
-    /// - box aerial battle
+    /// - scoreline
     fn get_shots(
         &self,
         team: &squad::Squad,
-        players: impl Iterator<Item = &'a &'a player::Player>,
-        stats: &GameStats,
-        aerial_atk: f32,
-        opp_aerial_def: f32,
+        players: &[player::Player],
         opp_def_str: f32,
-    ) -> u8 {
+        intent: f32,
+    ) -> f32 {
         let mut rng = self.rng.write().unwrap();
         let mut shots: f32 = 0.0;
         for player in players {
@@ -321,31 +2332,30 @@ impl<'a> Game<'a> {
             shots += player.technique as f32 * 0.15;
         }
 
-        shots /= opp_def_str;
+        shots /= opp_def_str.max(1.0);
 
         if team.tactics.shoot_more_often {
             shots *= 1.25;
         }
+        shots *= intent;
 
-        shots *= rng.gen_range(0.25..0.75) * 10.0;
-
-        let mut total = shots.round() as u8;
+        shots * rng.gen_range(0.25..0.75) * 10.0
+    }
 
-        // aerial duels (crosses)
-        for _ in 0..stats.crosses {
-            if rng.gen_bool((aerial_atk / opp_aerial_def) as f64 * 0.25) {
-                total += 1;
-            }
-        }
-        total
+    /// get chance of a cross ending in a header
+    /// decided by the box aerial battle
+    fn get_header_chance(&self, aerial_atk: f32, opp_aerial_def: f32) -> f64 {
+        ((aerial_atk / opp_aerial_def) as f64 * 0.25).min(1.0)
     }
 
+    /// return chance of each shot being on target
+    /// set pieces depend on the taker, everything else on the team's shooting
     fn get_shots_on_target(
         &self,
         team: &squad::Squad,
-        players: impl Iterator<Item = &'a &'a player::Player>,
-        stats: &GameStats,
-    ) -> u8 {
+        players: &[player::Player],
+        attempts: &[Attempt],
+    ) -> Vec<f64> {
         let mut rng = self.rng.write().unwrap();
         let mut shooting_acc = 0.01;
         let mut i = 0;
@@ -363,7 +2373,7 @@ impl<'a> Game<'a> {
             i += 1;
             shooting_acc += p.shooting as f32 * multiplier;
         }
-        shooting_acc /= i as f32;
+        shooting_acc /= i.max(1) as f32;
 
         if team.tactics.shoot_more_often {
             shooting_acc *= 0.8;
@@ -371,40 +2381,110 @@ impl<'a> Game<'a> {
 
         shooting_acc *= 0.01;
 
-        let mut total = 0;
-        for _ in 0..stats.shots {
-            let rnd = rng.gen_range(0.8..1.3);
-            let mut chance = shooting_acc as f64 * rnd;
-            if chance >= 1.0 {
-                chance = 0.99;
-            }
-            if rng.gen_bool(chance) {
-                total += 1;
-            }
-        }
-        total
+        attempts
+            .iter()
+            .map(|attempt| {
+                let taker = attempt.shooter;
+                let chance = match attempt.goal_type {
+                    event::GoalType::Penalty => {
+                        0.75 + (taker.set_pieces as f64 + taker.shooting as f64) / 510.0 * 0.2
+                    }
+                    event::GoalType::DirectFreeKick => taker.set_pieces as f64 * 0.0025,
+                    _ => {
+                        let rnd = rng.gen_range(0.8..1.3);
+                        let mut chance = shooting_acc as f64 * rnd;
+                        if chance >= 1.0 {
+                            chance = 0.99;
+                        }
+                        chance
+                    }
+                };
+                probability(chance)
+            })
+            .collect()
     }
 
-    fn get_goals(&self, opp: &squad::Squad, stats: &GameStats) -> u8 {
+    /// return whether each shot is (on target, in the net) against the keeper in goal at the time
+    /// shots go in by their xG, more often past a poor keeper and less often past a good one
+    /// shots that don't go in still hit the target often enough to keep the chances of hitting it
+    fn get_goals(
+        &self,
+        keepers: &[Option<&player::Player>],
+        attempts: &[Attempt],
+        xg: &[f32],
+        on_target: &[f64],
+    ) -> Vec<(bool, bool)> {
         let mut rng = self.rng.write().unwrap();
-        let keeper = opp
-            .players
+        attempts
             .iter()
-            .find(|p| p.position == position::Position::Goalkeeper)
-            .unwrap();
+            .zip(xg)
+            .zip(on_target)
+            .zip(keepers)
+            .map(|(((attempt, &xg), &on_target), keeper)| {
+                // nobody in goal, every shot on target goes in
+                let keeper = match keeper {
+                    Some(keeper) => keeper,
+                    None => {
+                        let on_target = rng.gen_bool(on_target);
+                        return (on_target, on_target);
+                    }
+                };
+                let keeper_factor = match attempt.goal_type {
+                    event::GoalType::Penalty => 1.25 - keeper.goalkeeping as f64 / 510.0,
+                    _ => 1.5 - keeper.goalkeeping as f64 / 255.0,
+                };
+                let goal_chance = probability(xg as f64 * keeper_factor);
+                if rng.gen_bool(goal_chance) {
+                    return (true, true);
+                }
+                let saved_chance = (on_target - goal_chance) / (1.0 - goal_chance);
+                (rng.gen_bool(probability(saved_chance)), false)
+            })
+            .collect()
+    }
 
-        let mut goals = 0;
-        for _ in 0..stats.shots_on_target {
-            // can opponent keeper save the shot
-            if !rng.gen_bool(keeper.goalkeeping as f64 * 0.0035) {
-                // keeper fails to make a save
-                goals += 1;
+    /// get xG of a shot, the chance of it going in past an average keeper
+    /// calculated based on:
+    /// - where the shot is taken from
+    /// - shooter shooting, long shots, heading, technique or set pieces, by the kind of shot
+    /// - pressure from the defensive strength of the opponent, penalties aside
+    fn get_xg(&self, attempt: &Attempt, opp_def_str: f32) -> f32 {
+        let p = attempt.shooter;
+        // (xG of an average shooter, skill of this one)
+        let (xg, skill) = match attempt.origin {
+            event::ShotOrigin::OpenPlay => {
+                (0.1, p.shooting as f32 * 0.7 + p.technique as f32 * 0.3)
+            }
+            event::ShotOrigin::Header => (0.08, p.heading as f32 * 0.8 + p.technique as f32 * 0.2),
+            event::ShotOrigin::LongShot => {
+                (0.03, p.long_shots as f32 * 0.7 + p.technique as f32 * 0.3)
             }
+            event::ShotOrigin::FreeKick => (
+                0.05,
+                p.set_pieces as f32 * 0.4 + p.long_shots as f32 * 0.3 + p.technique as f32 * 0.3,
+            ),
+            event::ShotOrigin::Penalty => {
+                (0.76, p.shooting as f32 * 0.5 + p.set_pieces as f32 * 0.5)
+            }
+        };
+        let mut xg = xg * (0.5 + skill / 255.0);
+        if attempt.origin != event::ShotOrigin::Penalty {
+            xg *= 2.0 / (1.0 + opp_def_str / AVERAGE_DEF_STRENGTH);
+        }
+        xg.clamp(0.01, 0.95)
+    }
+
+    /// get chance of an open play shot being taken from outside the box
+    /// decided by how keen the shooter is to try from distance, strikers get closer in
+    fn get_long_shot_chance(&self, shooter: &player::Player) -> f64 {
+        let chance = 0.1 + shooter.long_shots as f64 / 255.0 * 0.3;
+        match shooter.position {
+            position::Position::Striker => chance * 0.5,
+            _ => chance,
         }
-        goals
     }
 
-    /// get number of crosses for the team
+    /// get number of crosses for the team over a match
     /// factors:
     /// - tactics: cross_more_often, attack width
     /// - opp_tactics: compactness
@@ -416,7 +2496,7 @@ impl<'a> Game<'a> {
         stats: &GameStats,
         wide_atk: (f32, f32),
         opp_wide_def: (f32, f32),
-    ) -> u8 {
+    ) -> f32 {
         let mut rng = self.rng.write().unwrap();
 
         let mut crosses: f32 = rng.gen_range(1.0..30.0);
@@ -425,33 +2505,28 @@ impl<'a> Game<'a> {
                 crosses *= 0.7;
             }
             tactics::Width::Balanced => {
-                crosses *=
-                    ((wide_atk.0 + wide_atk.1) / 2.0) / ((opp_wide_def.0 + opp_wide_def.1) / 2.0);
+                crosses *= ((wide_atk.0 + wide_atk.1) / 2.0)
+                    / ((opp_wide_def.0 + opp_wide_def.1) / 2.0).max(1.0);
             }
             tactics::Width::Left => {
-                crosses *= wide_atk.0 / opp_wide_def.1;
+                // nobody left to defend the flank
+                crosses *= wide_atk.0 / opp_wide_def.1.max(1.0);
             }
             tactics::Width::Right => {
-                crosses *= wide_atk.1 / opp_wide_def.0;
+                crosses *= wide_atk.1 / opp_wide_def.0.max(1.0);
             }
         }
         if team.tactics.cross_more_often {
             crosses *= 2.0;
         }
-        crosses *= stats.possession;
-        crosses.round() as u8
+        crosses * stats.possession
     }
 
-    /// get number of corners, freekicks and penalties
+    /// get number of corners for the team over a match
     /// calculated based on:
-    /// - tactics: shoot_more_often
-    /// - fouls of opponent team
-    fn get_set_pieces(
-        &self,
-        team: &squad::Squad,
-        stats: &GameStats,
-        opp_stats: &GameStats,
-    ) -> (u8, u8, u8) {
+    /// - tactics: shoot_more_often, attack width
+    /// - possession
+    fn get_corners(&self, team: &squad::Squad, stats: &GameStats) -> f32 {
         let mut rng = self.rng.write().unwrap();
         let mut corner_rate: f32 = 10.0; // base rate
         if team.tactics.shoot_more_often {
@@ -465,33 +2540,21 @@ impl<'a> Game<'a> {
             corner_rate += rng.gen_range(-2.5..3.5);
         }
 
-        let corners: u8 = rng.gen_range(0..corner_rate.round() as u8);
-
-        let mut freekicks: u8 = 0;
-        if opp_stats.fouls > 0 {
-            freekicks = rng.gen_range(
-                opp_stats.yellow_cards.len() as u8
-                    ..(opp_stats.fouls + opp_stats.yellow_cards.len() as u8),
-            );
-        }
-
-        let penalties: f32 = opp_stats.fouls as f32 * rng.gen_range(0.01..0.1);
-
-        (corners, freekicks, penalties.round() as u8)
+        rng.gen_range(0..(corner_rate.round() as u8).max(1)) as f32
     }
 
-    fn get_team_poss_score(&self, squad: &squad::Squad, stats: &GameStats) -> f32 {
+    fn get_team_poss_score(&self, squad: &squad::Squad, players: &[player::Player]) -> f32 {
         let mut rng = self.rng.write().unwrap();
         // --- tactics: pressure, buildup, ball retention, pass_range ---
         let pressure = squad.tactics.defense_line as f32
-            * (u8::MAX - squad.tactics.compactness + 1) as f32
+            * (u8::MAX as f32 - squad.tactics.compactness as f32 + 1.0)
             * (squad.tactics.aggression as f32 * 0.1)
             * 0.01
             + 1.0;
 
         let tact_score = pressure
-            + (((u8::MAX - squad.tactics.build_up_speed + 1) as f32
-                * (u8::MAX - squad.tactics.pass_range + 1) as f32)
+            + (((u8::MAX as f32 - squad.tactics.build_up_speed as f32 + 1.0)
+                * (u8::MAX as f32 - squad.tactics.pass_range as f32 + 1.0))
                 + 1.0)
                 * 0.01;
         // tact range: 1.2 -> 23084.97
@@ -499,11 +2562,7 @@ impl<'a> Game<'a> {
         let mut formation_score = 0.0;
 
         let mut players_score = 0.0;
-        for &p in squad
-            .players
-            .iter()
-            .filter(|p| !stats.red_cards.contains(&p.id))
-        {
+        for p in players {
             let pos_score = match p.position {
                 position::Position::DefensiveMidfield
                 | position::Position::LeftMidfield
@@ -552,7 +2611,7 @@ impl<'a> Game<'a> {
             players_score += p.passing as f32
                 + (p.technique as f32 * 0.75)
                 + (p.fitness as f32 * 0.5)
-                + (p.stamina as f32 / squad.tactics.defense_line as f32 * 0.5)
+                + (p.stamina as f32 / squad.tactics.defense_line.max(1) as f32 * 0.5)
                     * (player_score_multiplier
                         * (1.0 + p.form as f32 * 0.01)
                         * (p.morale as f32 * 0.01)
@@ -574,13 +2633,9 @@ impl<'a> Game<'a> {
 
     /// get squad defensive strength score
     /// return defense_strength
-    fn get_squad_def_strength(&self, team: &squad::Squad, stats: &GameStats) -> f32 {
+    fn get_squad_def_strength(&self, players: &[player::Player]) -> f32 {
         let mut def = 0.0;
-        for &p in team
-            .players
-            .iter()
-            .filter(|p| !stats.red_cards.contains(&p.id))
-        {
+        for p in players {
             if p.position == position::Position::Goalkeeper {
                 def += p.goalkeeping as f32 * 0.5;
                 continue;
@@ -599,16 +2654,6 @@ impl<'a> Game<'a> {
         def
     }
 
-    fn get_players(
-        &self,
-        team: &'a squad::Squad,
-        stats: GameStats,
-    ) -> impl Iterator<Item = &'a &'a player::Player> {
-        team.players
-            .iter()
-            .filter(move |p| !stats.red_cards.contains(&p.id))
-    }
-
     /// get defensive aerial strength
     /// box defensive capabilities to deal with high balls
     /// factors:
@@ -618,7 +2663,7 @@ impl<'a> Game<'a> {
     /// - heading
     /// - defensive positioning
     /// - marking
-    fn get_def_aerial(&self, players: impl Iterator<Item = &'a &'a player::Player>) -> f32 {
+    fn get_def_aerial(&self, players: &[player::Player]) -> f32 {
         let mut def = 0.0;
         for p in players {
             if p.position == position::Position::Goalkeeper {
@@ -653,7 +2698,7 @@ impl<'a> Game<'a> {
     /// - strength
     /// - heading
     /// - attack positioning
-    fn get_atk_aerial(&self, players: impl Iterator<Item = &'a &'a player::Player>) -> f32 {
+    fn get_atk_aerial(&self, players: &[player::Player]) -> f32 {
         let mut atk = 0.0;
         for p in players {
             {
@@ -683,11 +2728,7 @@ impl<'a> Game<'a> {
     /// factors:
     /// - tactics: attack_width
     /// - players: technique, pace, attack_positioning, playstyle
-    fn get_wide_atk(
-        &self,
-        team: &squad::Squad,
-        players: impl Iterator<Item = &'a &'a player::Player>,
-    ) -> (f32, f32) {
+    fn get_wide_atk(&self, team: &squad::Squad, players: &[player::Player]) -> (f32, f32) {
         let mut left = 0.0;
         let mut right = 0.0;
         for p in players {
@@ -731,11 +2772,7 @@ impl<'a> Game<'a> {
     }
 
     /// get defensive capabilities on the flanks
-    fn get_wide_def(
-        &self,
-        team: &squad::Squad,
-        players: impl Iterator<Item = &'a &'a player::Player>,
-    ) -> (f32, f32) {
+    fn get_wide_def(&self, team: &squad::Squad, players: &[player::Player]) -> (f32, f32) {
         let mut left = 0.0;
         let mut right = 0.0;
         for p in players {
@@ -781,3 +2818,97 @@ impl<'a> Game<'a> {
         (left, right)
     }
 }
+
+/// check both squads can be played against each other
+/// - lineups: see `squad::Squad::validate`
+/// - ratings of every player and both managers, and tactics of both teams, are between 1 and 255
+/// - no player is picked by both teams
+pub fn validate(home: &squad::Squad, away: &squad::Squad) -> Result<(), error::FootsimError> {
+    for squad in [home, away] {
+        squad.validate()?;
+        squad.tactics.validate()?;
+        squad.manager.validate()?;
+        for p in squad.players.iter().chain(squad.subs.iter()) {
+            p.validate()?;
+        }
+    }
+    let home_ids = home.players.iter().chain(home.subs.iter()).map(|p| p.id);
+    for id in home_ids {
+        if away
+            .players
+            .iter()
+            .chain(away.subs.iter())
+            .any(|p| p.id == id)
+        {
+            return Err(error::FootsimError::PlayerInBothSquads(id));
+        }
+    }
+    Ok(())
+}
+
+/// turn an expected number of occurrences into a whole number
+/// keeping the fraction as the chance of one more
+fn sample_count(rng: &mut impl Rng, expected: f32) -> u8 {
+    let expected = expected.max(0.0);
+    let count = expected.floor();
+    let extra = rng.gen_bool(probability((expected - count) as f64)) as u8;
+    (count as u8).saturating_add(extra)
+}
+
+/// return `chance` as a probability `gen_bool` takes, anything not a number is no chance
+fn probability(chance: f64) -> f64 {
+    match chance.is_nan() {
+        true => 0.0,
+        false => chance.clamp(0.0, 1.0),
+    }
+}
+
+/// return how good a player is at winning the ball
+fn get_tackling(player: &player::Player) -> f32 {
+    player.tackling as f32 * 0.7 + player.defensive_positioning as f32 * 0.3
+}
+
+/// return how often a player in the position gets in a tackle
+fn get_tackle_multiplier(position: position::Position) -> f32 {
+    match position {
+        position::Position::CenterBack | position::Position::DefensiveMidfield => 1.0,
+        position::Position::LeftBack
+        | position::Position::RightBack
+        | position::Position::LeftWingBack
+        | position::Position::RightWingBack => 0.9,
+        position::Position::CenterMidfield => 0.7,
+        position::Position::LeftMidfield | position::Position::RightMidfield => 0.5,
+        position::Position::Goalkeeper => 0.0,
+        _ => 0.25,
+    }
+}
+
+/// return how much of the passing of a team goes through a player in the position
+fn get_pass_multiplier(position: position::Position) -> f32 {
+    match position {
+        position::Position::CenterMidfield => 1.2,
+        position::Position::DefensiveMidfield => 1.1,
+        position::Position::AttackingMidfield => 1.0,
+        position::Position::LeftBack
+        | position::Position::RightBack
+        | position::Position::LeftWingBack
+        | position::Position::RightWingBack
+        | position::Position::LeftMidfield
+        | position::Position::RightMidfield => 0.9,
+        position::Position::CenterBack => 0.8,
+        position::Position::Goalkeeper => 0.3,
+        _ => 0.6,
+    }
+}
+
+/// count occurrences of each player id, in order of first occurrence
+fn count_by_player(ids: &[u32]) -> Vec<(u32, u8)> {
+    let mut counts: Vec<(u32, u8)> = Vec::new();
+    for &id in ids {
+        match counts.iter_mut().find(|(p, _)| *p == id) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((id, 1)),
+        }
+    }
+    counts
+}
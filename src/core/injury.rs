@@ -0,0 +1,46 @@
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    // player is treated and plays on
+    Knock,
+    Minor,
+    Moderate,
+    Serious,
+}
+
+impl Severity {
+    /// return the range of days a player with an injury of this severity is out for
+    pub fn days_out(self) -> RangeInclusive<u16> {
+        match self {
+            Severity::Knock => 0..=0,
+            Severity::Minor => 1..=7,
+            Severity::Moderate => 8..=28,
+            Severity::Serious => 29..=180,
+        }
+    }
+
+    /// return whether the player has to leave the pitch
+    pub fn forces_off(self) -> bool {
+        self != Severity::Knock
+    }
+}
+
+/// an injury picked up during a match
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Injury {
+    pub player_id: u32,
+    pub minute: u8,
+    pub severity: Severity,
+    // expected number of days the player is out for
+    pub days_out: u16,
+}
+
+impl Injury {
+    /// return whether the player is available again `days` after the match
+    pub fn is_available(&self, days: u16) -> bool {
+        days >= self.days_out
+    }
+}
@@ -1,3 +1,7 @@
+use super::error;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Manager {
     pub id: u32,
     pub name: String,
@@ -8,3 +12,21 @@ pub struct Manager {
     pub management: u8,
     pub coaching: u8,
 }
+
+impl Manager {
+    /// check every rating is between 1 and 255
+    pub fn validate(&self) -> Result<(), error::FootsimError> {
+        let ratings = [
+            ("tactical", self.tactical),
+            ("management", self.management),
+            ("coaching", self.coaching),
+        ];
+        match ratings.iter().find(|(_, value)| *value == 0) {
+            Some(&(attribute, _)) => Err(error::FootsimError::ManagerAttribute {
+                manager_id: self.id,
+                attribute,
+            }),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use rand::Rng;
+
+use super::{manager, tactics};
+
+/// how managers change the tactics of their team during a match
+/// a manager looks at the match every so often and at half time, and moves the tactics away from
+/// the plan the team started with to suit the score, the minute, red cards and how tired the
+/// team is, going back to the plan once nothing calls for a change
+/// better tacticians look more often and get closer to the tactics the situation calls for
+#[derive(Clone, Debug)]
+pub struct ManagerAI {
+    // minutes between looks at the match for the best tactician, the worst takes three times as long
+    pub interval: u8,
+    // mean condition (100 = fresh) of the team below which the manager eases off
+    pub fatigue_threshold: f32,
+}
+
+/// the state of a match as a manager sees it
+#[derive(Clone, Debug)]
+pub struct Situation {
+    pub minute: u8,
+    // minutes the match lasts, extra time included once it's being played
+    pub length: u8,
+    // goals ahead, behind if negative, over both legs of a tie
+    pub goal_difference: i16,
+    // players still on the pitch
+    pub players: u8,
+    pub opp_players: u8,
+    // mean condition (100 = fresh) of the players on the pitch
+    pub condition: f32,
+}
+
+impl Default for ManagerAI {
+    fn default() -> Self {
+        Self {
+            interval: 10,
+            fatigue_threshold: 75.0,
+        }
+    }
+}
+
+impl ManagerAI {
+    /// return minutes between looks at the match for the manager
+    pub fn get_interval(&self, manager: &manager::Manager) -> u8 {
+        let skill = manager.tactical as f32 / u8::MAX as f32;
+        (self.interval.max(1) as f32 * (3.0 - skill * 2.0)).round() as u8
+    }
+
+    /// return tactics for the situation, moved from the `plan` the team started with
+    /// calculated based on:
+    /// - scoreline and minute: chasing a game pushes up and plays quicker, a lead late on is
+    ///   protected by sitting deep
+    /// - players sent off on either side
+    /// - condition of the team: a tired team drops deeper and slows down
+    /// - manager tactical: how much of the change is made, and how far off the mark it is
+    pub fn adjust(
+        &self,
+        manager: &manager::Manager,
+        plan: &tactics::Tactics,
+        situation: &Situation,
+        rng: &mut impl Rng,
+    ) -> tactics::Tactics {
+        let mut tactics = plan.clone();
+        // changes to the sliders
+        let (mut line, mut aggression, mut tempo) = (0.0, 0.0, 0.0);
+        // the scoreline counts for more the less time is left
+        let late = (situation.minute as f32 / situation.length.max(1) as f32).clamp(0.0, 1.0);
+        match situation.goal_difference {
+            goals if goals < 0 => {
+                let urgency = late * if goals < -1 { 1.5 } else { 1.0 };
+                line += 80.0 * urgency;
+                aggression += 50.0 * urgency;
+                tempo += 70.0 * urgency;
+                if urgency > 0.5 {
+                    tactics.shoot_more_often = true;
+                    tactics.cross_more_often = true;
+                    tactics.attack_width = tactics::Width::Balanced;
+                }
+            }
+            goals if goals > 0 => {
+                // a one goal lead is the one worth parking the bus for
+                let urgency = late * late * if goals > 1 { 0.5 } else { 1.0 };
+                line -= 90.0 * urgency;
+                aggression -= 20.0 * urgency;
+                tempo -= 70.0 * urgency;
+                if urgency > 0.5 {
+                    tactics.shoot_more_often = false;
+                    tactics.cross_more_often = false;
+                    tactics.attack_width = tactics::Width::Central;
+                }
+            }
+            _ => {}
+        }
+        // a man down drops deeper and stays out of tackles, a man up pushes on
+        let men = situation.players as f32 - situation.opp_players as f32;
+        line += men * 25.0;
+        tempo += men * 15.0;
+        aggression += men.min(0.0) * 20.0;
+        let tired = (self.fatigue_threshold - situation.condition).max(0.0);
+        line -= tired * 1.5;
+        tempo -= tired;
+        aggression -= tired * 0.5;
+
+        let skill = manager.tactical as f32 / u8::MAX as f32;
+        // share of the change made, and how far it can be misjudged either way
+        let reaction = 0.4 + skill * 0.6;
+        let error = (1.0 - skill) * 0.5;
+        let mut slide = |value: u8, change: f32| -> u8 {
+            if change == 0.0 {
+                return value;
+            }
+            let change = change * (reaction + rng.gen_range(-error..=error));
+            (value as f32 + change).round().clamp(1.0, u8::MAX as f32) as u8
+        };
+        tactics.defense_line = slide(plan.defense_line, line);
+        tactics.aggression = slide(plan.aggression, aggression);
+        tactics.build_up_speed = slide(plan.build_up_speed, tempo);
+        // a manager who doesn't read the game can miss the switches
+        let switches = tactics.shoot_more_often != plan.shoot_more_often
+            || tactics.cross_more_often != plan.cross_more_often
+            || tactics.attack_width != plan.attack_width;
+        if switches && !rng.gen_bool((0.5 + skill * 0.5) as f64) {
+            tactics.shoot_more_often = plan.shoot_more_often;
+            tactics.cross_more_often = plan.cross_more_often;
+            tactics.attack_width = plan.attack_width.clone();
+        }
+        tactics
+    }
+}
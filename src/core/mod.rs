@@ -1,7 +1,18 @@
+pub mod batch;
+pub mod commentary;
+pub mod competition;
+pub mod error;
+pub mod event;
+pub mod formation;
 pub mod game;
+pub mod injury;
 pub mod manager;
+pub mod manager_ai;
 pub mod player;
 pub mod position;
+pub mod rating;
+pub mod rules;
 pub mod squad;
 pub mod style;
+pub mod substitution;
 pub mod tactics;
@@ -1,5 +1,9 @@
-use super::{position, style};
+use std::collections::HashMap;
 
+use super::{error, position, style};
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub id: u32,
     pub name: String,
@@ -38,6 +42,16 @@ pub struct Player {
     // position
     pub position: position::Position,
     pub playstyle: style::PlayStyle,
+    // how familiar (0 - 255) the player is with positions other than their natural one
+    // positions left out fall back on how close they are to the natural position
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub familiarity: HashMap<position::Position, u8>,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Player {
@@ -73,6 +87,123 @@ impl Player {
             attack_positioning: 0,
             position: position::Position::Goalkeeper,
             playstyle: style::PlayStyle::Default,
+            familiarity: HashMap::new(),
+        }
+    }
+
+    /// check every rating is between 1 and 255
+    pub fn validate(&self) -> Result<(), error::FootsimError> {
+        let ratings = [
+            ("morale", self.morale),
+            ("form", self.form),
+            ("tactical", self.tactical),
+            ("leadership", self.leadership),
+            ("decision_making", self.decision_making),
+            ("creativity", self.creativity),
+            ("vision", self.vision),
+            ("fitness", self.fitness),
+            ("pace", self.pace),
+            ("strength", self.strength),
+            ("stamina", self.stamina),
+            ("jumping", self.jumping),
+            ("passing", self.passing),
+            ("technique", self.technique),
+            ("heading", self.heading),
+            ("set_pieces", self.set_pieces),
+            ("tackling", self.tackling),
+            ("marking", self.marking),
+            ("goalkeeping", self.goalkeeping),
+            ("defensive_positioning", self.defensive_positioning),
+            ("shooting", self.shooting),
+            ("long_shots", self.long_shots),
+            ("attack_positioning", self.attack_positioning),
+        ];
+        match ratings.iter().find(|(_, value)| *value == 0) {
+            Some(&(attribute, _)) => Err(error::FootsimError::Attribute {
+                player_id: self.id,
+                attribute,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// return how familiar (0 - 255) the player is with playing `position`
+    pub fn get_familiarity(&self, position: position::Position) -> u8 {
+        if position == self.position {
+            return u8::MAX;
+        }
+        self.familiarity
+            .get(&position)
+            .copied()
+            .unwrap_or_else(|| self.position.get_similarity(position))
+    }
+
+    /// return a copy of the player playing `position`, with attributes dropped by how
+    /// unfamiliar they are with it
+    /// positional sense suffers the most, then decisions, then technique, physical not at all
+    pub fn in_position(&self, position: position::Position) -> Self {
+        let unfamiliarity = 1.0 - self.get_familiarity(position) as f32 / u8::MAX as f32;
+        let positional = |value: u8| (value as f32 * (1.0 - unfamiliarity * 0.5)).round() as u8;
+        let mental = |value: u8| (value as f32 * (1.0 - unfamiliarity * 0.3)).round() as u8;
+        let technical = |value: u8| (value as f32 * (1.0 - unfamiliarity * 0.15)).round() as u8;
+        Self {
+            name: self.name.clone(),
+            familiarity: self.familiarity.clone(),
+            position,
+            // positional
+            tactical: positional(self.tactical),
+            marking: positional(self.marking),
+            defensive_positioning: positional(self.defensive_positioning),
+            attack_positioning: positional(self.attack_positioning),
+            // mental
+            decision_making: mental(self.decision_making),
+            creativity: mental(self.creativity),
+            vision: mental(self.vision),
+            // technical
+            passing: technical(self.passing),
+            technique: technical(self.technique),
+            heading: technical(self.heading),
+            tackling: technical(self.tackling),
+            goalkeeping: technical(self.goalkeeping),
+            shooting: technical(self.shooting),
+            long_shots: technical(self.long_shots),
+            ..*self
+        }
+    }
+
+    /// return a copy of the player with attributes worn down by `condition` (1.0 = fresh)
+    /// physical attributes suffer the most, then mental, then technical
+    pub fn with_condition(&self, condition: f32) -> Self {
+        let tiredness = 1.0 - condition.clamp(0.0, 1.0);
+        let physical = |value: u8| (value as f32 * (1.0 - tiredness * 0.5)).round() as u8;
+        let mental = |value: u8| (value as f32 * (1.0 - tiredness * 0.3)).round() as u8;
+        let technical = |value: u8| (value as f32 * (1.0 - tiredness * 0.25)).round() as u8;
+        Self {
+            name: self.name.clone(),
+            familiarity: self.familiarity.clone(),
+            // mental
+            decision_making: mental(self.decision_making),
+            creativity: mental(self.creativity),
+            vision: mental(self.vision),
+            defensive_positioning: mental(self.defensive_positioning),
+            attack_positioning: mental(self.attack_positioning),
+            // physical
+            fitness: physical(self.fitness),
+            pace: physical(self.pace),
+            strength: physical(self.strength),
+            stamina: physical(self.stamina),
+            jumping: physical(self.jumping),
+            // technical
+            passing: technical(self.passing),
+            technique: technical(self.technique),
+            heading: technical(self.heading),
+            set_pieces: technical(self.set_pieces),
+            tackling: technical(self.tackling),
+            marking: technical(self.marking),
+            goalkeeping: technical(self.goalkeeping),
+            shooting: technical(self.shooting),
+            long_shots: technical(self.long_shots),
+            ..*self
         }
     }
 }
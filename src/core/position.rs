@@ -1,4 +1,5 @@
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Position {
     Goalkeeper,
     LeftBack,
@@ -15,3 +16,50 @@ pub enum Position {
     RightWing,
     Striker,
 }
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Area {
+    Goal,
+    Defence,
+    Midfield,
+    Attack,
+}
+
+impl Position {
+    /// area of the pitch the position plays in
+    pub fn area(&self) -> Area {
+        match self {
+            Position::Goalkeeper => Area::Goal,
+            Position::LeftBack
+            | Position::CenterBack
+            | Position::RightBack
+            | Position::LeftWingBack
+            | Position::RightWingBack => Area::Defence,
+            Position::DefensiveMidfield
+            | Position::CenterMidfield
+            | Position::AttackingMidfield
+            | Position::LeftMidfield
+            | Position::RightMidfield => Area::Midfield,
+            Position::LeftWing | Position::RightWing | Position::Striker => Area::Attack,
+        }
+    }
+
+    /// return how familiar (0 - 255) a player of this natural position is with playing `other`
+    /// calculated based on:
+    /// - area of the pitch: the further apart the less familiar
+    /// - keepers and outfield players don't know each other's job
+    pub fn get_similarity(&self, other: Position) -> u8 {
+        if *self == other {
+            return u8::MAX;
+        }
+        if *self == Position::Goalkeeper || other == Position::Goalkeeper {
+            return 0;
+        }
+        match (self.area() as i8 - other.area() as i8).abs() {
+            0 => 170,
+            1 => 100,
+            _ => 40,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use super::{event, position};
+
+/// rating of a player who did nothing of note in a draw
+const BASE_RATING: f32 = 6.0;
+
+/// rating of a player who took the pitch
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerRating {
+    pub player_id: u32,
+    pub side: event::Side,
+    pub position: position::Position,
+    // 1 - 10, to one decimal place
+    pub rating: f32,
+}
+
+/// ratings of everyone who played in a match
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchRatings {
+    // home players first, each team in the order they took the pitch
+    pub ratings: Vec<PlayerRating>,
+    // best rated player, the first of them on a tie
+    pub man_of_the_match: Option<u32>,
+}
+
+impl MatchRatings {
+    /// rate the players of each team, as (player id, position played), from the events of the match
+    /// calculated based on:
+    /// - goals, assists, shots, key passes
+    /// - tackles, saves
+    /// - fouls, cards and errors: own goals, penalties given away
+    /// - goals conceded by keepers and defenders, a clean sheet by the share of the match played
+    /// - result of the team
+    pub fn from_events(
+        home: &[(u32, position::Position)],
+        away: &[(u32, position::Position)],
+        events: &[event::MatchEvent],
+    ) -> Self {
+        let goals = |side: event::Side| {
+            events
+                .iter()
+                .filter(|e| e.side == Some(side))
+                .filter(|e| matches!(e.kind, event::EventKind::Goal { .. }))
+                .count()
+        };
+        let (home_goals, away_goals) = (goals(event::Side::Home), goals(event::Side::Away));
+        let mut ratings = Vec::new();
+        for (side, players, scored, conceded) in [
+            (event::Side::Home, home, home_goals, away_goals),
+            (event::Side::Away, away, away_goals, home_goals),
+        ] {
+            let result = match scored.cmp(&conceded) {
+                std::cmp::Ordering::Greater => 0.5,
+                std::cmp::Ordering::Equal => 0.0,
+                std::cmp::Ordering::Less => -0.5,
+            };
+            for &(player_id, position) in players {
+                let mut rating = BASE_RATING + result + get_contribution(player_id, events);
+                // a clean sheet is down to the back line, and so are goals let in
+                if position.area() <= position::Area::Defence {
+                    rating += match conceded {
+                        0 => 0.5 * get_share_played(player_id, events),
+                        goals => goals as f32 * -0.25,
+                    };
+                }
+                ratings.push(PlayerRating {
+                    player_id,
+                    side,
+                    position,
+                    rating: (rating.clamp(1.0, 10.0) * 10.0).round() / 10.0,
+                });
+            }
+        }
+        let man_of_the_match = ratings
+            .iter()
+            .fold(None::<&PlayerRating>, |best, r| match best {
+                Some(best) if best.rating >= r.rating => Some(best),
+                _ => Some(r),
+            })
+            .map(|r| r.player_id);
+        Self {
+            ratings,
+            man_of_the_match,
+        }
+    }
+
+    pub fn get_rating(&self, player_id: u32) -> Option<f32> {
+        self.ratings
+            .iter()
+            .find(|r| r.player_id == player_id)
+            .map(|r| r.rating)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// return how much the actions of a player add to or take off their rating
+fn get_contribution(player_id: u32, events: &[event::MatchEvent]) -> f32 {
+    let mut contribution = 0.0;
+    for (i, e) in events.iter().enumerate() {
+        contribution += match e.kind {
+            event::EventKind::Goal {
+                player_id: scorer,
+                goal_type: event::GoalType::OwnGoal,
+                ..
+            } if scorer == player_id => -1.0,
+            event::EventKind::Goal {
+                player_id: scorer, ..
+            } if scorer == player_id => 1.0,
+            event::EventKind::Goal {
+                assist_id: Some(assister),
+                ..
+            } if assister == player_id => 0.6,
+            event::EventKind::Shot {
+                player_id: shooter,
+                on_target,
+                ..
+            } if shooter == player_id => match on_target {
+                true => 0.2,
+                false => 0.05,
+            },
+            // key pass
+            event::EventKind::Shot {
+                assist_id: Some(assister),
+                ..
+            } if assister == player_id => 0.15,
+            event::EventKind::Save { keeper_id, .. } if keeper_id == player_id => 0.3,
+            event::EventKind::Tackle {
+                player_id: tackler, ..
+            } if tackler == player_id => 0.1,
+            event::EventKind::Foul {
+                player_id: fouler, ..
+            } if fouler == player_id => match gives_away_penalty(events, i) {
+                true => -0.6,
+                false => -0.1,
+            },
+            // a second yellow counts as a yellow and a red
+            event::EventKind::Card {
+                player_id: booked,
+                card,
+                ..
+            } if booked == player_id => match card {
+                event::Card::Yellow => -0.4,
+                event::Card::Red => -1.5,
+            },
+            _ => 0.0,
+        };
+    }
+    contribution
+}
+
+/// return share (0 - 1) of the match the player was on the pitch for
+/// players come on as substitutes and go off when substituted, sent off or injured too badly to
+/// play on
+fn get_share_played(player_id: u32, events: &[event::MatchEvent]) -> f32 {
+    let length = events.iter().map(|e| e.minute).max().unwrap_or(0);
+    let (mut on, mut off) = (0, length);
+    for e in events {
+        match e.kind {
+            event::EventKind::Substitution { player_on, .. } if player_on == player_id => {
+                on = e.minute
+            }
+            event::EventKind::Substitution { player_off, .. } if player_off == player_id => {
+                off = off.min(e.minute)
+            }
+            event::EventKind::Card {
+                player_id: booked,
+                card: event::Card::Red,
+                ..
+            } if booked == player_id => off = off.min(e.minute),
+            event::EventKind::Injury {
+                player_id: injured,
+                severity,
+                ..
+            } if injured == player_id && severity.forces_off() => off = off.min(e.minute),
+            _ => {}
+        }
+    }
+    match length {
+        0 => 1.0,
+        _ => off.saturating_sub(on) as f32 / length as f32,
+    }
+}
+
+/// return whether the foul at `i` gave away a penalty
+/// the set piece for a foul follows it in the same minute, before the next foul
+fn gives_away_penalty(events: &[event::MatchEvent], i: usize) -> bool {
+    let minute = events[i].minute;
+    events[i + 1..]
+        .iter()
+        .take_while(|e| e.minute == minute && !matches!(e.kind, event::EventKind::Foul { .. }))
+        .any(|e| matches!(e.kind, event::EventKind::Penalty { .. }))
+}
@@ -0,0 +1,24 @@
+/// how a match is decided
+#[derive(Clone, Debug)]
+pub struct MatchRules {
+    // the match, or the tie over both legs, needs a winner
+    pub knockout: bool,
+    // play extra time before a penalty shootout when the tie is level
+    pub extra_time: bool,
+    // goals scored away from home decide a level two legged tie
+    pub away_goals: bool,
+    // score of the first leg as (goals of this match's home team, goals of this match's away team)
+    // `None` for single matches, and for the first leg itself
+    pub first_leg: Option<(u8, u8)>,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            knockout: false,
+            extra_time: true,
+            away_goals: false,
+            first_leg: None,
+        }
+    }
+}
@@ -1,9 +1,293 @@
-use super::{manager, player, tactics};
+use std::sync::Arc;
 
+use super::{formation, manager, player, position, tactics};
+
+/// a club and its whole roster, the squad for each match is picked from it
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Team {
+    pub id: u32,
+    pub name: String,
+    pub manager: Arc<manager::Manager>,
+    pub players: Vec<Arc<player::Player>>,
+    // tactics and formation picked squads start out with
+    pub tactics: tactics::Tactics,
+    pub formation: formation::Formation,
+}
+
+/// most players a team names on the bench when picking its own squad
+pub const BENCH_SIZE: usize = 7;
+
+/// the players a team takes into a match
+/// players are shared with the team they're picked from, so squads are cheap to clone
+/// squads loaded without a formation line up in the natural positions of the starting eleven
 #[derive(Clone)]
-pub struct Squad<'a> {
-    pub manager: &'a manager::Manager,
-    pub players: [&'a player::Player; 11],
-    pub subs: Vec<&'a player::Player>,
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SquadFile")
+)]
+pub struct Squad {
+    // name of the team, empty for a squad not picked from one
+    pub name: String,
+    pub manager: Arc<manager::Manager>,
+    // starting eleven, each plays the position of their slot in the formation
+    pub players: [Arc<player::Player>; 11],
+    pub subs: Vec<Arc<player::Player>>,
     pub tactics: tactics::Tactics,
+    pub formation: formation::Formation,
+}
+
+/// something that stops a lineup from being played
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LineupProblem {
+    // number of players in the starting eleven, when it isn't 11
+    PlayerCount(usize),
+    // number of keepers in the formation, when it isn't 1
+    Goalkeepers(usize),
+    // player picked more than once
+    Duplicate(u32),
+    // player not in the roster of the team
+    UnknownPlayer(u32),
+}
+
+/// every problem found with a lineup
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LineupError(pub Vec<LineupProblem>);
+
+/// a squad as written in a file, where the formation can be left out
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SquadFile {
+    #[serde(default)]
+    name: String,
+    manager: Arc<manager::Manager>,
+    players: Vec<Arc<player::Player>>,
+    subs: Vec<Arc<player::Player>>,
+    tactics: tactics::Tactics,
+    formation: Option<formation::Formation>,
+}
+
+impl Team {
+    pub fn get_player(&self, player_id: u32) -> Option<&Arc<player::Player>> {
+        self.players.iter().find(|p| p.id == player_id)
+    }
+
+    /// return country of the team, the one most of its players come from
+    /// on a tie the country of the manager goes first, then the first one in the roster
+    pub fn get_country_id(&self) -> u32 {
+        let mut countries: Vec<(u32, usize)> = Vec::new();
+        for player in &self.players {
+            match countries
+                .iter_mut()
+                .find(|(id, _)| *id == player.country_id)
+            {
+                Some((_, count)) => *count += 1,
+                None => countries.push((player.country_id, 1)),
+            }
+        }
+        countries
+            .iter()
+            .enumerate()
+            .max_by_key(|&(i, &(id, count))| {
+                (count, id == self.manager.country_id, std::cmp::Reverse(i))
+            })
+            .map(|(_, &(id, _))| id)
+            .unwrap_or(self.manager.country_id)
+    }
+
+    /// pick the squad the manager would, with the tactics and formation of the team
+    /// the roster is in order of preference: each slot of the formation in turn goes to the
+    /// player most familiar with it, the first in the roster on a tie
+    /// the next players in the roster make up the bench
+    pub fn pick_squad(&self) -> Result<Squad, LineupError> {
+        let mut left = self.players.iter().map(|p| p.id).collect::<Vec<_>>();
+        let mut eleven = Vec::new();
+        for slot in self.formation.slots() {
+            let best = left
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &id)| Some((i, self.get_player(id)?.get_familiarity(slot))))
+                .max_by_key(|&(i, familiarity)| (familiarity, std::cmp::Reverse(i)))
+                .map(|(i, _)| i);
+            match best {
+                Some(i) => eleven.push(left.remove(i)),
+                None => break,
+            }
+        }
+        left.truncate(BENCH_SIZE);
+        self.select(&eleven, &left)
+    }
+
+    /// pick a squad by player id, with the tactics and formation of the team
+    pub fn select(&self, eleven: &[u32], subs: &[u32]) -> Result<Squad, LineupError> {
+        let unknown = eleven
+            .iter()
+            .chain(subs.iter())
+            .filter(|&&id| self.get_player(id).is_none())
+            .map(|&id| LineupProblem::UnknownPlayer(id))
+            .collect::<Vec<_>>();
+        let pick = |ids: &[u32]| {
+            ids.iter()
+                .filter_map(|&id| self.get_player(id).cloned())
+                .collect::<Vec<_>>()
+        };
+        Squad::from_parts(
+            self.name.clone(),
+            self.manager.clone(),
+            pick(eleven),
+            pick(subs),
+            self.tactics.clone(),
+            self.formation.clone(),
+            unknown,
+        )
+    }
 }
+
+impl Squad {
+    /// a squad of players not picked from a team
+    pub fn new(
+        manager: manager::Manager,
+        players: Vec<player::Player>,
+        subs: Vec<player::Player>,
+        tactics: tactics::Tactics,
+        formation: formation::Formation,
+    ) -> Result<Self, LineupError> {
+        Self::from_parts(
+            String::new(),
+            Arc::new(manager),
+            players.into_iter().map(Arc::new).collect(),
+            subs.into_iter().map(Arc::new).collect(),
+            tactics,
+            formation,
+            Vec::new(),
+        )
+    }
+
+    /// check the squad can be played, e.g. after changing it by hand
+    pub fn validate(&self) -> Result<(), LineupError> {
+        let problems = get_problems(&self.players, &self.subs, &self.formation);
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(LineupError(problems)),
+        }
+    }
+
+    /// return each player of the starting eleven with the position of their slot
+    pub fn get_lineup(&self) -> impl Iterator<Item = (position::Position, &Arc<player::Player>)> {
+        self.formation.slots().into_iter().zip(self.players.iter())
+    }
+
+    /// build a squad, failing with `problems` found earlier and any problems of the lineup
+    fn from_parts(
+        name: String,
+        manager: Arc<manager::Manager>,
+        players: Vec<Arc<player::Player>>,
+        subs: Vec<Arc<player::Player>>,
+        tactics: tactics::Tactics,
+        formation: formation::Formation,
+        mut problems: Vec<LineupProblem>,
+    ) -> Result<Self, LineupError> {
+        problems.extend(get_problems(&players, &subs, &formation));
+        if !problems.is_empty() {
+            return Err(LineupError(problems));
+        }
+        Ok(Self {
+            name,
+            manager,
+            players: players
+                .try_into()
+                .map_err(|p: Vec<_>| LineupError(vec![LineupProblem::PlayerCount(p.len())]))?,
+            subs,
+            tactics,
+            formation,
+        })
+    }
+}
+
+/// return every problem with a lineup, empty if it can be played
+fn get_problems(
+    players: &[Arc<player::Player>],
+    subs: &[Arc<player::Player>],
+    formation: &formation::Formation,
+) -> Vec<LineupProblem> {
+    let mut problems = Vec::new();
+    if players.len() != 11 {
+        problems.push(LineupProblem::PlayerCount(players.len()));
+    }
+    if formation.goalkeepers() != 1 {
+        problems.push(LineupProblem::Goalkeepers(formation.goalkeepers()));
+    }
+    let ids = players
+        .iter()
+        .chain(subs.iter())
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    for (i, id) in ids.iter().enumerate() {
+        // once for each player, where they're picked the second time
+        if ids[..i].iter().filter(|&other| other == id).count() == 1 {
+            problems.push(LineupProblem::Duplicate(*id));
+        }
+    }
+    problems
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SquadFile> for Squad {
+    type Error = LineupError;
+
+    fn try_from(file: SquadFile) -> Result<Self, Self::Error> {
+        // without a formation everyone plays their natural position
+        let formation = file.formation.unwrap_or_else(|| {
+            match file
+                .players
+                .iter()
+                .map(|p| p.position)
+                .collect::<Vec<_>>()
+                .try_into()
+            {
+                Ok(slots) => formation::Formation::Custom(slots),
+                Err(_) => formation::Formation::default(),
+            }
+        });
+        Self::from_parts(
+            file.name,
+            file.manager,
+            file.players,
+            file.subs,
+            file.tactics,
+            formation,
+            Vec::new(),
+        )
+    }
+}
+
+impl std::fmt::Display for LineupProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineupProblem::PlayerCount(n) => {
+                write!(f, "the starting eleven has {} players instead of 11", n)
+            }
+            LineupProblem::Goalkeepers(n) => {
+                write!(f, "the formation has {} goalkeepers instead of 1", n)
+            }
+            LineupProblem::Duplicate(id) => write!(f, "player {} is picked more than once", id),
+            LineupProblem::UnknownPlayer(id) => write!(f, "player {} isn't in the team", id),
+        }
+    }
+}
+
+impl std::fmt::Display for LineupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid lineup: ")?;
+        for (i, problem) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LineupError {}
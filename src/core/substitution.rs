@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use super::{player, position};
+
+/// how many changes a team can make, and when the manager considers making them
+#[derive(Clone, Debug)]
+pub struct SubstitutionPolicy {
+    // most substitutions a team can make
+    pub max_subs: u8,
+    // most stoppages a team can use to make them, half time not included
+    pub max_windows: u8,
+    // condition (100 = fresh) below which a tired player is taken off
+    pub fatigue_threshold: f32,
+    // take off booked players who are likely to pick up a second yellow
+    pub protect_booked: bool,
+    // minute after which the scoreline calls for a change
+    pub scoreline_minute: u8,
+}
+
+impl Default for SubstitutionPolicy {
+    fn default() -> Self {
+        Self {
+            max_subs: 5,
+            max_windows: 3,
+            fatigue_threshold: 65.0,
+            protect_booked: true,
+            scoreline_minute: 60,
+        }
+    }
+}
+
+/// pick the bench player to take over a slot of the formation playing `slot`
+/// the player most familiar with the slot comes on, the first on the bench on a tie
+/// `area` asks for a player of a given area of the pitch instead of a like for like change
+/// keepers only come on in goal
+pub fn pick_replacement(
+    bench: &[Arc<player::Player>],
+    slot: position::Position,
+    area: Option<position::Area>,
+) -> Option<usize> {
+    if let Some(i) = area.and_then(|area| bench.iter().position(|p| p.position.area() == area)) {
+        return Some(i);
+    }
+    let keeper = slot == position::Position::Goalkeeper;
+    bench
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| keeper || p.position != position::Position::Goalkeeper)
+        .max_by_key(|&(i, p)| (p.get_familiarity(slot), std::cmp::Reverse(i)))
+        .map(|(i, _)| i)
+}
@@ -1,4 +1,7 @@
-#[derive(Clone)]
+use super::error;
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tactics {
     // high defensive line = more pressure but more dangerous if press fails
     pub defense_line: u8,
@@ -18,7 +21,25 @@ pub struct Tactics {
     pub pass_range: u8,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+impl Tactics {
+    /// check every slider is between 1 and 255
+    pub fn validate(&self) -> Result<(), error::FootsimError> {
+        let sliders = [
+            ("defense_line", self.defense_line),
+            ("compactness", self.compactness),
+            ("aggression", self.aggression),
+            ("build_up_speed", self.build_up_speed),
+            ("pass_range", self.pass_range),
+        ];
+        match sliders.iter().find(|(_, value)| *value == 0) {
+            Some(&(tactic, _)) => Err(error::FootsimError::Tactic { tactic }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Width {
     Central,
     Left,
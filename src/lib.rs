@@ -0,0 +1,4 @@
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod core;
+mod tests;
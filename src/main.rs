@@ -1,7 +1,16 @@
-mod core;
-mod tests;
-
+#[cfg(feature = "cli")]
 fn main() {
+    use clap::Parser;
+
     env_logger::init();
-    println!("Hello, world!");
+    if let Err(e) = footsim::cli::Cli::parse().run() {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+fn main() {
+    eprintln!("footsim was built without the cli feature");
+    std::process::exit(1);
 }
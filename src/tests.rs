@@ -0,0 +1,2120 @@
+#![cfg(test)]
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::core::{
+    batch, commentary, competition, error, event, formation, game, injury, manager, manager_ai,
+    player, position, rating, rules, squad, style, substitution, tactics,
+};
+
+fn test_manager(id: u32, team_id: u32) -> manager::Manager {
+    manager::Manager {
+        id,
+        name: format!("manager {}", id),
+        team_id,
+        country_id: 1,
+        tactical: 150,
+        management: 150,
+        coaching: 150,
+    }
+}
+
+fn test_player(id: u32, team_id: u32, position: position::Position) -> player::Player {
+    let mut p = player::Player::new();
+    p.id = id;
+    p.name = format!("player {}", id);
+    p.team_id = team_id;
+    p.country_id = 1;
+    p.height = 180;
+    p.morale = 150;
+    p.form = 150;
+    p.tactical = 150;
+    p.leadership = 150;
+    p.decision_making = 150;
+    p.creativity = 150;
+    p.vision = 150;
+    p.fitness = 150;
+    p.pace = 150;
+    p.strength = 150;
+    p.stamina = 150;
+    p.jumping = 150;
+    p.passing = 150;
+    p.technique = 150;
+    p.heading = 150;
+    p.set_pieces = 150;
+    p.tackling = 150;
+    p.marking = 150;
+    p.goalkeeping = 150;
+    p.defensive_positioning = 150;
+    p.shooting = 150;
+    p.long_shots = 150;
+    p.attack_positioning = 150;
+    p.position = position;
+    p.playstyle = style::PlayStyle::Default;
+    p
+}
+
+/// 4-4-2 lineup followed by 5 subs, ids start at `team_id * 100`
+fn test_players(team_id: u32) -> Vec<player::Player> {
+    use position::Position::*;
+    [
+        Goalkeeper,
+        LeftBack,
+        CenterBack,
+        CenterBack,
+        RightBack,
+        LeftMidfield,
+        CenterMidfield,
+        CenterMidfield,
+        RightMidfield,
+        Striker,
+        Striker,
+        Goalkeeper,
+        CenterBack,
+        CenterMidfield,
+        LeftWing,
+        Striker,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, pos)| test_player(team_id * 100 + i as u32, team_id, pos))
+    .collect()
+}
+
+fn test_tactics() -> tactics::Tactics {
+    tactics::Tactics {
+        defense_line: 128,
+        compactness: 128,
+        aggression: 128,
+        build_up_speed: 128,
+        attack_width: tactics::Width::Balanced,
+        shoot_more_often: false,
+        cross_more_often: false,
+        pass_range: 128,
+    }
+}
+
+fn test_squad(manager: &manager::Manager, players: &[player::Player]) -> squad::Squad {
+    squad::Squad::new(
+        manager.clone(),
+        players[..11].to_vec(),
+        players[11..].to_vec(),
+        test_tactics(),
+        formation::Formation::FourFourTwo,
+    )
+    .unwrap()
+}
+
+/// match between teams 1 and 2 with squads of the given players, not played yet
+fn test_game(
+    home_players: &[player::Player],
+    away_players: &[player::Player],
+    seed: u64,
+) -> game::Game {
+    game::Game::with_seed(
+        test_squad(&test_manager(1, 1), home_players),
+        test_squad(&test_manager(2, 2), away_players),
+        seed,
+    )
+    .unwrap()
+}
+
+/// match between teams 1 and 2 of `test_players`, played with `seed`
+fn play_seeded(seed: u64) -> game::Game {
+    let mut game = test_game(&test_players(1), &test_players(2), seed);
+    game.play().unwrap();
+    game
+}
+
+/// team of `test_players` in a 4-4-2, roster in order of the lineup
+fn test_team(team_id: u32) -> squad::Team {
+    squad::Team {
+        id: team_id,
+        name: format!("team {}", team_id),
+        manager: std::sync::Arc::new(test_manager(team_id, team_id)),
+        players: test_players(team_id)
+            .into_iter()
+            .map(std::sync::Arc::new)
+            .collect(),
+        tactics: test_tactics(),
+        formation: formation::Formation::FourFourTwo,
+    }
+}
+
+#[test]
+fn game_plays_two_halves() {
+    let game = play_seeded(1);
+
+    for stats in [game.get_home_stats(), game.get_away_stats()] {
+        assert_eq!(stats.halves.len(), 2);
+        let (first, second) = (&stats.halves[0], &stats.halves[1]);
+        assert_eq!(stats.goals, first.goals + second.goals);
+        assert_eq!(stats.shots, first.shots + second.shots);
+        assert_eq!(stats.fouls, first.fouls + second.fouls);
+        assert_eq!(
+            stats.yellow_cards.len(),
+            first.yellow_cards.len() + second.yellow_cards.len()
+        );
+        assert_eq!(
+            stats.red_cards.len(),
+            first.red_cards.len() + second.red_cards.len()
+        );
+        assert!(stats.halves.iter().all(|h| h.halves.is_empty()));
+    }
+    let (home_ht, away_ht) = game.get_half_time_score().unwrap();
+    assert!(home_ht <= game.get_home_stats().goals);
+    assert!(away_ht <= game.get_away_stats().goals);
+}
+
+#[test]
+fn possession_is_split_between_teams() {
+    let game = play_seeded(2);
+
+    let total = game.get_home_stats().possession + game.get_away_stats().possession;
+    assert!((total - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn timeline_is_ordered_and_framed() {
+    let game = play_seeded(3);
+
+    let events = game.get_events();
+    assert!(events.windows(2).all(|w| w[0].minute <= w[1].minute));
+    assert_eq!(events.first().unwrap().kind, event::EventKind::KickOff);
+    assert_eq!(events.last().unwrap().kind, event::EventKind::FullTime);
+    assert_eq!(events.last().unwrap().minute, game::HALF_LENGTH * 2);
+    let half_time = events
+        .iter()
+        .find(|e| e.kind == event::EventKind::HalfTime)
+        .unwrap();
+    assert_eq!(half_time.minute, game::HALF_LENGTH);
+}
+
+#[test]
+fn timeline_agrees_with_stats() {
+    for seed in 0..20 {
+        let game = play_seeded(seed);
+
+        for (side, stats) in [
+            (event::Side::Home, game.get_home_stats()),
+            (event::Side::Away, game.get_away_stats()),
+        ] {
+            let events = game
+                .get_events()
+                .iter()
+                .filter(|e| e.side == Some(side))
+                .collect::<Vec<_>>();
+            let count = |f: fn(&event::EventKind) -> bool| {
+                events.iter().filter(|e| f(&e.kind)).count() as u8
+            };
+            assert_eq!(
+                stats.goals,
+                count(|k| matches!(k, event::EventKind::Goal { .. }))
+            );
+            assert_eq!(
+                stats.shots,
+                count(|k| matches!(k, event::EventKind::Shot { .. }))
+            );
+            assert_eq!(
+                stats.shots_on_target,
+                count(|k| matches!(
+                    k,
+                    event::EventKind::Shot {
+                        on_target: true,
+                        ..
+                    }
+                ))
+            );
+            assert_eq!(
+                stats.fouls,
+                count(|k| matches!(k, event::EventKind::Foul { .. }))
+            );
+            assert_eq!(
+                stats.corners,
+                count(|k| matches!(k, event::EventKind::Corner { .. }))
+            );
+            // own goals come with no shot
+            assert!(stats.goals - stats.own_goals.len() as u8 <= stats.shots_on_target);
+            assert_eq!(
+                stats.red_cards.len() as u8,
+                count(|k| matches!(
+                    k,
+                    event::EventKind::Card {
+                        card: event::Card::Red,
+                        ..
+                    }
+                ))
+            );
+        }
+    }
+}
+
+#[test]
+fn sent_off_players_take_no_further_part() {
+    let (mut home_players, away_players) = (test_players(1), test_players(2));
+    // reckless defenders get sent off every now and then
+    for p in home_players.iter_mut().skip(1).take(4) {
+        p.decision_making = 5;
+        p.tackling = 5;
+    }
+    for seed in 0..20 {
+        let mut game = test_game(&home_players, &away_players, seed);
+        game.play().unwrap();
+
+        let events = game.get_events();
+        for (i, sent_off) in events.iter().enumerate() {
+            let player = match sent_off.kind {
+                event::EventKind::Card {
+                    player_id,
+                    card: event::Card::Red,
+                    ..
+                } => player_id,
+                _ => continue,
+            };
+            for e in events.iter().skip(i + 1) {
+                let involved = match e.kind {
+                    event::EventKind::Shot { player_id, .. }
+                    | event::EventKind::Goal { player_id, .. }
+                    | event::EventKind::Cross { player_id }
+                    | event::EventKind::Corner { player_id }
+                    | event::EventKind::FreeKick { player_id }
+                    | event::EventKind::Penalty { player_id } => player_id == player,
+                    event::EventKind::Foul {
+                        player_id,
+                        fouled_id,
+                    } => (player_id == player && e.minute > sent_off.minute) || fouled_id == player,
+                    _ => false,
+                };
+                assert!(!involved, "{} involved in {:?} after red card", player, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn same_seed_replays_the_same_match() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let play = |seed: u64| {
+        let game = play_seeded(seed);
+        (
+            game.get_events().to_vec(),
+            game.get_home_stats(),
+            game.get_away_stats(),
+        )
+    };
+
+    assert_eq!(play(42), play(42));
+    assert_ne!(play(42).0, play(43).0);
+
+    // seeding the generator directly plays the same match as `with_seed`
+    let mut game = game::Game::with_rng(
+        test_squad(&home_manager, &home_players),
+        test_squad(&away_manager, &away_players),
+        StdRng::seed_from_u64(42),
+    )
+    .unwrap();
+    game.play().unwrap();
+    assert_eq!(game.get_events(), play(42).0.as_slice());
+}
+
+#[test]
+fn goals_are_attributed_to_players() {
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let team_of = |id: u32| {
+        home_players
+            .iter()
+            .chain(away_players.iter())
+            .find(|p| p.id == id)
+            .unwrap()
+            .team_id
+    };
+    for seed in 0..20 {
+        let game = play_seeded(seed);
+
+        for (side, team_id, stats) in [
+            (event::Side::Home, 1, game.get_home_stats()),
+            (event::Side::Away, 2, game.get_away_stats()),
+        ] {
+            assert_eq!(
+                stats.goals as usize,
+                stats.scorers.len() + stats.own_goals.len()
+            );
+            let by_player = stats.get_goals_by_player();
+            assert_eq!(
+                by_player.iter().map(|(_, g)| *g as usize).sum::<usize>(),
+                stats.scorers.len()
+            );
+            assert!(stats.scorers.iter().all(|&id| team_of(id) == team_id));
+            assert!(stats.own_goals.iter().all(|&id| team_of(id) != team_id));
+            assert!(stats.assists.iter().all(|&id| team_of(id) == team_id));
+
+            for e in game.get_events().iter().filter(|e| e.side == Some(side)) {
+                let (scorer, assist, goal_type) = match e.kind {
+                    event::EventKind::Goal {
+                        player_id,
+                        assist_id,
+                        goal_type,
+                    } => (player_id, assist_id, goal_type),
+                    _ => continue,
+                };
+                assert_ne!(Some(scorer), assist);
+                let earlier = |kind: event::EventKind| {
+                    game.get_events()
+                        .iter()
+                        .any(|p| p.minute == e.minute && p.side == Some(side) && p.kind == kind)
+                };
+                match goal_type {
+                    event::GoalType::Header => {
+                        let crosser = assist.expect("headers are assisted by the crosser");
+                        assert!(earlier(event::EventKind::Cross { player_id: crosser }));
+                    }
+                    event::GoalType::Penalty => {
+                        assert!(earlier(event::EventKind::Penalty { player_id: scorer }));
+                        assert_eq!(assist, None);
+                    }
+                    event::GoalType::DirectFreeKick => {
+                        assert!(earlier(event::EventKind::FreeKick { player_id: scorer }));
+                        assert_eq!(assist, None);
+                    }
+                    event::GoalType::OwnGoal => assert_eq!(assist, None),
+                    event::GoalType::OpenPlay => {}
+                }
+            }
+        }
+    }
+}
+
+/// player ids involved in an event
+fn involved(kind: &event::EventKind) -> Vec<u32> {
+    match *kind {
+        event::EventKind::Cross { player_id }
+        | event::EventKind::Card { player_id, .. }
+        | event::EventKind::Corner { player_id }
+        | event::EventKind::FreeKick { player_id }
+        | event::EventKind::Penalty { player_id }
+        | event::EventKind::Injury { player_id, .. } => vec![player_id],
+        event::EventKind::Goal {
+            player_id,
+            assist_id,
+            ..
+        }
+        | event::EventKind::Shot {
+            player_id,
+            assist_id,
+            ..
+        } => vec![player_id].into_iter().chain(assist_id).collect(),
+        event::EventKind::Save {
+            keeper_id,
+            shooter_id,
+        } => vec![keeper_id, shooter_id],
+        event::EventKind::Foul {
+            player_id,
+            fouled_id,
+        } => vec![player_id, fouled_id],
+        event::EventKind::Tackle {
+            player_id,
+            tackled_id,
+        } => vec![player_id, tackled_id],
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn substitutions_follow_the_policy() {
+    let (mut home_players, away_players) = (test_players(1), test_players(2));
+    // a team that tires quickly
+    for p in home_players.iter_mut() {
+        p.stamina = 10;
+    }
+    let mut subs_made = 0;
+    for seed in 0..20 {
+        let mut game = test_game(&home_players, &away_players, seed);
+        game.set_substitution_policy(substitution::SubstitutionPolicy {
+            max_subs: 3,
+            max_windows: 2,
+            ..Default::default()
+        });
+        game.play().unwrap();
+
+        let stats = game.get_home_stats();
+        assert!(stats.substitutions.len() <= 3);
+        subs_made += stats.substitutions.len();
+        let mut windows = stats
+            .substitutions
+            .iter()
+            .map(|(off, _)| {
+                game.get_events()
+                    .iter()
+                    .find(|e| matches!(e.kind, event::EventKind::Substitution { player_off, .. } if player_off == *off))
+                    .unwrap()
+            })
+            // changes at half time and for injuries don't use up a window
+            .filter(|e| {
+                e.minute != game::HALF_LENGTH
+                    && !matches!(
+                        e.kind,
+                        event::EventKind::Substitution {
+                            reason: event::SubReason::Injury,
+                            ..
+                        }
+                    )
+            })
+            .map(|e| e.minute)
+            .collect::<Vec<_>>();
+        windows.dedup();
+        assert!(windows.len() <= 2);
+
+        let bench = home_players
+            .iter()
+            .skip(11)
+            .map(|p| p.id)
+            .collect::<Vec<_>>();
+        for (i, e) in game.get_events().iter().enumerate() {
+            if e.side != Some(event::Side::Home) {
+                continue;
+            }
+            let (off, on) = match e.kind {
+                event::EventKind::Substitution {
+                    player_off,
+                    player_on,
+                    ..
+                } => (player_off, player_on),
+                _ => continue,
+            };
+            let (before, after) = game.get_events().split_at(i);
+            assert!(bench.contains(&on));
+            // substitutes can go off again once they've come on, e.g. injured
+            assert!(
+                !bench.contains(&off)
+                    || before.iter().any(|e| matches!(
+                        e.kind,
+                        event::EventKind::Substitution { player_on, .. } if player_on == off
+                    ))
+            );
+            assert!(before.iter().all(|e| !involved(&e.kind).contains(&on)));
+            assert!(after.iter().all(|e| !involved(&e.kind).contains(&off)));
+        }
+    }
+    assert!(subs_made > 0);
+}
+
+#[test]
+fn condition_drains_with_intensity() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let no_subs = substitution::SubstitutionPolicy {
+        max_subs: 0,
+        ..Default::default()
+    };
+    let full_time_condition = |tactics: tactics::Tactics, seed: u64| {
+        let mut home = test_squad(&home_manager, &home_players);
+        home.tactics = tactics;
+        let mut game =
+            game::Game::with_seed(home, test_squad(&away_manager, &away_players), seed).unwrap();
+        game.set_substitution_policy(no_subs.clone());
+        game.play().unwrap();
+        game
+    };
+
+    let mut pressing = test_tactics();
+    pressing.defense_line = 255;
+    pressing.aggression = 255;
+    pressing.build_up_speed = 255;
+    let mut sitting = test_tactics();
+    sitting.defense_line = 20;
+    sitting.aggression = 20;
+    sitting.build_up_speed = 20;
+
+    let (mut pressed, mut sat) = (0.0, 0.0);
+    for seed in 0..5 {
+        let (pressing, sitting) = (
+            full_time_condition(pressing.clone(), seed),
+            full_time_condition(sitting.clone(), seed),
+        );
+        for p in home_players.iter().take(11) {
+            let condition = pressing.get_condition(p.id).unwrap();
+            assert!(condition < 100.0);
+            pressed += condition;
+            sat += sitting.get_condition(p.id).unwrap();
+        }
+        // keepers barely tire, the bench doesn't at all
+        let keeper = pressing.get_condition(home_players[0].id).unwrap();
+        let defender = pressing.get_condition(home_players[1].id).unwrap();
+        assert!(keeper > defender);
+        for p in home_players.iter().skip(11) {
+            assert_eq!(pressing.get_condition(p.id), Some(100.0));
+        }
+        assert_eq!(pressing.get_conditions().len(), 32);
+    }
+    assert!(pressed < sat);
+}
+
+#[test]
+fn tired_players_are_worse() {
+    let player = test_player(1, 1, position::Position::CenterMidfield);
+    let fresh = player.with_condition(1.0);
+    assert_eq!(fresh.pace, player.pace);
+    assert_eq!(fresh.passing, player.passing);
+    let tired = player.with_condition(0.4);
+    assert!(tired.pace < player.pace);
+    assert!(tired.passing < player.passing);
+    assert!(tired.decision_making < player.decision_making);
+    // physical attributes suffer the most
+    assert!(tired.pace < tired.passing);
+
+    // condition carries over into the next match
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let mut game = test_game(&home_players, &away_players, 1);
+    game.set_condition(home_players[5].id, 40.0).unwrap();
+    game.set_condition(999, 40.0).unwrap();
+    assert_eq!(game.get_condition(home_players[5].id), Some(40.0));
+    assert_eq!(game.get_condition(999), None);
+}
+
+#[test]
+fn out_of_position_players_are_worse() {
+    let mut player = test_player(1, 1, position::Position::CenterBack);
+    let natural = player.in_position(position::Position::CenterBack);
+    assert_eq!(natural.marking, player.marking);
+    let full_back = player.in_position(position::Position::LeftBack);
+    let striker = player.in_position(position::Position::Striker);
+    assert_eq!(striker.position, position::Position::Striker);
+    assert!(striker.marking < full_back.marking);
+    assert!(full_back.marking < player.marking);
+    // positional sense suffers the most, physical attributes not at all
+    assert!(striker.attack_positioning < striker.shooting);
+    assert_eq!(striker.pace, player.pace);
+    player
+        .familiarity
+        .insert(position::Position::Striker, u8::MAX);
+    assert_eq!(
+        player.in_position(position::Position::Striker).marking,
+        player.marking
+    );
+
+    // the most familiar player on the bench comes on, keepers only in goal
+    let bench = [
+        position::Position::Goalkeeper,
+        position::Position::Striker,
+        position::Position::CenterBack,
+    ]
+    .map(|pos| std::sync::Arc::new(test_player(2, 1, pos)));
+    let pick = |slot| substitution::pick_replacement(&bench, slot, None);
+    assert_eq!(pick(position::Position::CenterBack), Some(2));
+    assert_eq!(pick(position::Position::LeftBack), Some(2));
+    assert_eq!(pick(position::Position::RightWing), Some(1));
+    assert_eq!(pick(position::Position::Goalkeeper), Some(0));
+
+    // a lineup turned upside down does worse than the same players in their positions
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let goals = |eleven: Vec<player::Player>| {
+        let home = squad::Squad::new(
+            home_manager.clone(),
+            eleven,
+            Vec::new(),
+            test_tactics(),
+            formation::Formation::FourFourTwo,
+        )
+        .unwrap();
+        let mut batch = batch::Batch::new(home, test_squad(&away_manager, &away_players), 200);
+        batch.set_seed(1);
+        let summary = batch.run().unwrap();
+        (summary.home.goals.mean, summary.away.goals.mean)
+    };
+    let mut upside_down = home_players[..11].to_vec();
+    upside_down[1..].reverse();
+    let (natural_for, natural_against) = goals(home_players[..11].to_vec());
+    let (shuffled_for, shuffled_against) = goals(upside_down);
+    assert!(shuffled_for < natural_for);
+    assert!(shuffled_against > natural_against);
+}
+
+#[test]
+fn injured_players_are_forced_off() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, mut away_players) = (test_players(1), test_players(2));
+    // clumsy opponents
+    for p in away_players.iter_mut() {
+        p.tackling = 20;
+    }
+    let mut injuries = 0;
+    let mut replaced = 0;
+    for (seed, max_subs) in (0..40).zip([0, 5].into_iter().cycle()) {
+        let mut away = test_squad(&away_manager, &away_players);
+        away.tactics.aggression = 255;
+        let mut game =
+            game::Game::with_seed(test_squad(&home_manager, &home_players), away, seed).unwrap();
+        game.set_substitution_policy(substitution::SubstitutionPolicy {
+            max_subs,
+            ..Default::default()
+        });
+        for p in home_players.iter() {
+            game.set_condition(p.id, 30.0).unwrap();
+        }
+        game.play().unwrap();
+
+        let stats = game.get_home_stats();
+        let events = game.get_events();
+        let recorded = events
+            .iter()
+            .filter(|e| matches!(e.kind, event::EventKind::Injury { .. }))
+            .filter(|e| e.side == Some(event::Side::Home))
+            .count();
+        assert_eq!(stats.injuries.len(), recorded);
+        for injury in stats.injuries.iter() {
+            injuries += 1;
+            assert!(injury.severity.days_out().contains(&injury.days_out));
+            assert!(injury.is_available(injury.days_out));
+            if !injury.severity.forces_off() {
+                continue;
+            }
+            let at = events
+                .iter()
+                .position(|e| {
+                    e.minute == injury.minute
+                        && matches!(e.kind, event::EventKind::Injury { player_id, severity, .. } if player_id == injury.player_id && severity == injury.severity)
+                })
+                .unwrap();
+            // replaced at the next stoppage, or the team plays on short
+            for e in events[at + 1..].iter() {
+                match e.kind {
+                    event::EventKind::Substitution {
+                        player_off, reason, ..
+                    } if player_off == injury.player_id => {
+                        assert_eq!(reason, event::SubReason::Injury);
+                        assert!(max_subs > 0);
+                        replaced += 1;
+                    }
+                    _ => assert!(!involved(&e.kind).contains(&injury.player_id)),
+                }
+            }
+        }
+    }
+    assert!(injuries > 0);
+    assert!(replaced > 0);
+    // knocks are played through
+    assert!(!injury::Severity::Knock.forces_off());
+}
+
+#[test]
+fn knockout_ties_have_a_winner() {
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let variants = [
+        (None, true, false),
+        (None, false, false),
+        (Some((2, 1)), true, true),
+        (Some((3, 3)), true, true),
+    ];
+    let (mut extra_time, mut shootouts) = (0, 0);
+    for seed in 0..40 {
+        let (first_leg, et, away_goals) = variants[seed as usize % variants.len()];
+        let mut game = test_game(&home_players, &away_players, seed);
+        game.set_rules(rules::MatchRules {
+            knockout: true,
+            extra_time: et,
+            away_goals,
+            first_leg,
+        });
+        game.play().unwrap();
+
+        let (home, away) = (game.get_home_stats(), game.get_away_stats());
+        let (first_home, first_away) = first_leg.unwrap_or((0, 0));
+        // level on aggregate and away goals after `halves` halves
+        let level = |halves: usize| {
+            let goals = |stats: &game::GameStats| {
+                stats
+                    .halves
+                    .iter()
+                    .take(halves)
+                    .map(|h| h.goals)
+                    .sum::<u8>()
+            };
+            let (h, a) = (first_home + goals(&home), first_away + goals(&away));
+            h == a && !(away_goals && first_leg.is_some() && first_home != goals(&away))
+        };
+        let events = game.get_events();
+        let played_extra_time = events.iter().any(|e| e.kind == event::EventKind::ExtraTime);
+        assert_eq!(played_extra_time, et && level(2));
+        assert_eq!(home.halves.len(), if played_extra_time { 4 } else { 2 });
+        let full_time = events
+            .iter()
+            .find(|e| e.kind == event::EventKind::FullTime)
+            .unwrap();
+        assert_eq!(
+            full_time.minute,
+            game::HALF_LENGTH * 2 + played_extra_time as u8 * game::EXTRA_TIME_LENGTH * 2
+        );
+        extra_time += played_extra_time as u8;
+
+        // penalties only when still level, and they always settle the tie
+        let winner = game.get_winner();
+        assert!(winner.is_some());
+        match game.get_shootout_score() {
+            Some((h, a)) => {
+                assert!(level(4));
+                assert_ne!(h, a);
+                assert_eq!(
+                    winner,
+                    Some(if h > a {
+                        event::Side::Home
+                    } else {
+                        event::Side::Away
+                    })
+                );
+                let kicks = (home.shootout.len() as i32, away.shootout.len() as i32);
+                assert!((kicks.0 - kicks.1).abs() <= 1);
+                assert_eq!(home.goals, home.halves.iter().map(|h| h.goals).sum::<u8>());
+                shootouts += 1;
+            }
+            None => assert!(!level(4)),
+        }
+    }
+    assert!(extra_time > 0);
+    assert!(shootouts > 0);
+}
+
+#[test]
+fn second_yellow_is_a_red() {
+    let (mut home_players, away_players) = (test_players(1), test_players(2));
+    // reckless defenders pick up plenty of cards
+    for p in home_players.iter_mut().skip(1).take(4) {
+        p.decision_making = 5;
+        p.tackling = 5;
+    }
+    let mut second_yellows = 0;
+    for seed in 0..20 {
+        let mut game = test_game(&home_players, &away_players, seed);
+        game.play().unwrap();
+
+        let stats = game.get_home_stats();
+        assert_eq!(
+            stats.bookings.len(),
+            stats.yellow_cards.len() + stats.red_cards.len()
+        );
+        assert!(stats
+            .bookings
+            .windows(2)
+            .all(|w| w[0].minute <= w[1].minute));
+        for (player_id, yellows, reds) in stats.get_cards_by_player() {
+            // never more than two yellows or one red
+            assert!(yellows <= 2);
+            assert!(reds <= 1);
+            let bookings = stats
+                .bookings
+                .iter()
+                .filter(|b| b.player_id == player_id)
+                .collect::<Vec<_>>();
+            if yellows == 2 {
+                // the second yellow is followed straight away by a red
+                assert_eq!(reds, 1);
+                let red = bookings.last().unwrap();
+                assert_eq!(red.card, event::Card::Red);
+                assert_eq!(red.reason, event::CardReason::SecondYellow);
+                assert_eq!(red.minute, bookings[1].minute);
+                second_yellows += 1;
+            }
+            // no further part after a red
+            if let Some(red) = bookings.iter().find(|b| b.card == event::Card::Red) {
+                let at = game
+                    .get_events()
+                    .iter()
+                    .position(|e| {
+                        matches!(e.kind, event::EventKind::Card { player_id: id, card: event::Card::Red, .. } if id == player_id)
+                    })
+                    .unwrap();
+                assert_eq!(game.get_events()[at].minute, red.minute);
+                assert!(game.get_events()[at + 1..]
+                    .iter()
+                    .all(|e| !involved(&e.kind).contains(&player_id)));
+            }
+        }
+    }
+    assert!(second_yellows > 0);
+}
+
+#[test]
+fn batches_are_reproducible_across_threads() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let summaries = [1, 4].map(|threads| {
+        let mut batch = batch::Batch::new(
+            test_squad(&home_manager, &home_players),
+            test_squad(&away_manager, &away_players),
+            12,
+        );
+        batch.set_seed(7);
+        batch.set_threads(threads);
+        batch.run().unwrap()
+    });
+    assert_eq!(summaries[0], summaries[1]);
+
+    let summary = &summaries[0];
+    assert_eq!(summary.runs, 12);
+    assert!((summary.home_win + summary.draw + summary.away_win - 1.0).abs() < 1e-5);
+    assert_eq!(summary.scorelines.iter().map(|s| s.count).sum::<u32>(), 12);
+    assert!(summary
+        .scorelines
+        .windows(2)
+        .all(|w| w[0].count >= w[1].count));
+    let goals = summary
+        .scorelines
+        .iter()
+        .map(|s| s.home as f32 * s.count as f32)
+        .sum::<f32>()
+        / 12.0;
+    assert!((summary.home.goals.mean - goals).abs() < 1e-3);
+    assert!(summary.home.goals.min <= summary.home.goals.mean);
+    assert!(summary.home.goals.mean <= summary.home.goals.max);
+    assert!(summary.home.distance.min > 0.0);
+}
+
+#[test]
+fn teams_pick_squads_for_games_on_other_threads() {
+    fn is_send_static<T: Send + 'static>(_: &T) {}
+
+    let players = test_players(1);
+    let team = test_team(1);
+    let eleven: [u32; 11] = std::array::from_fn(|i| players[i].id);
+    let subs = players[11..].iter().map(|p| p.id).collect::<Vec<_>>();
+    let squad = team.select(&eleven, &subs).unwrap();
+    assert_eq!(squad.name, team.name);
+    assert!(squad
+        .players
+        .iter()
+        .zip(team.players.iter())
+        .all(|(picked, p)| std::sync::Arc::ptr_eq(picked, p)));
+    assert_eq!(squad.subs.len(), subs.len());
+
+    // unknown players and players picked twice
+    assert!(team.select(&eleven, &[999]).is_err());
+    assert!(team.select(&eleven, &[eleven[0]]).is_err());
+    // left to pick their own, each slot goes to the player most familiar with it
+    let picked = team.pick_squad().unwrap();
+    assert!(picked.players.iter().map(|p| p.id).eq(eleven));
+    assert!(picked.subs.iter().map(|p| p.id).eq(subs.iter().copied()));
+
+    // games own their squads, so they can be played anywhere
+    let (away_manager, away_players) = (test_manager(2, 2), test_players(2));
+    let games = (0..2)
+        .map(|seed| {
+            game::Game::with_seed(
+                squad.clone(),
+                test_squad(&away_manager, &away_players),
+                seed,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let handles = games
+        .into_iter()
+        .map(|mut game| {
+            is_send_static(&game);
+            std::thread::spawn(move || {
+                game.play().unwrap();
+                game.get_home_stats()
+            })
+        })
+        .collect::<Vec<_>>();
+    for (seed, handle) in handles.into_iter().enumerate() {
+        let mut game = game::Game::with_seed(
+            squad.clone(),
+            test_squad(&away_manager, &away_players),
+            seed as u64,
+        )
+        .unwrap();
+        game.play().unwrap();
+        assert_eq!(handle.join().unwrap(), game.get_home_stats());
+    }
+}
+
+#[test]
+fn lineups_are_validated_and_play_their_slots() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let lineup = |formation: formation::Formation, eleven: Vec<player::Player>| {
+        squad::Squad::new(
+            home_manager.clone(),
+            eleven,
+            home_players[11..].to_vec(),
+            test_tactics(),
+            formation,
+        )
+    };
+    for formation in [
+        formation::Formation::FourFourTwo,
+        formation::Formation::FourThreeThree,
+        formation::Formation::ThreeFiveTwo,
+        formation::Formation::FourTwoThreeOne,
+        formation::Formation::FiveThreeTwo,
+    ] {
+        assert_eq!(formation.goalkeepers(), 1);
+        assert!(lineup(formation, home_players[..11].to_vec()).is_ok());
+    }
+
+    // every problem is listed, not just the first
+    let mut short = home_players[..10].to_vec();
+    short[9] = short[1].clone();
+    let no_keeper = formation::Formation::Custom([position::Position::Striker; 11]);
+    let error = lineup(no_keeper, short).err().unwrap();
+    assert_eq!(
+        error.0,
+        vec![
+            squad::LineupProblem::PlayerCount(10),
+            squad::LineupProblem::Goalkeepers(0),
+            squad::LineupProblem::Duplicate(home_players[1].id),
+        ]
+    );
+    let team = test_team(1);
+    let error = team.select(&[100, 101, 999], &[998]).err().unwrap();
+    assert_eq!(
+        error.0,
+        vec![
+            squad::LineupProblem::UnknownPlayer(999),
+            squad::LineupProblem::UnknownPlayer(998),
+            squad::LineupProblem::PlayerCount(2),
+        ]
+    );
+
+    // the natural keeper plays up front and a striker goes in goal
+    let mut eleven = home_players[..11].to_vec();
+    eleven.swap(0, 9);
+    let swapped = lineup(formation::Formation::FourFourTwo, eleven).unwrap();
+    let mut game =
+        game::Game::with_seed(swapped, test_squad(&away_manager, &away_players), 3).unwrap();
+    game.set_substitution_policy(substitution::SubstitutionPolicy {
+        max_subs: 0,
+        ..Default::default()
+    });
+    game.play().unwrap();
+    let saves = game
+        .get_events()
+        .iter()
+        .filter_map(|e| match e.kind {
+            event::EventKind::Save { keeper_id, .. } => Some((e.side, keeper_id)),
+            _ => None,
+        })
+        .filter(|(side, _)| *side == Some(event::Side::Home))
+        .collect::<Vec<_>>();
+    assert!(!saves.is_empty());
+    assert!(saves
+        .iter()
+        .all(|&(_, keeper)| keeper == home_players[9].id));
+}
+
+#[test]
+fn bad_data_is_an_error_not_a_panic() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let away = || test_squad(&away_manager, &away_players);
+
+    let mut players = home_players.clone();
+    players[3].stamina = 0;
+    assert_eq!(
+        game::Game::new(test_squad(&home_manager, &players), away()).err(),
+        Some(error::FootsimError::Attribute {
+            player_id: players[3].id,
+            attribute: "stamina",
+        })
+    );
+    let mut manager = home_manager.clone();
+    manager.tactical = 0;
+    assert_eq!(
+        game::Game::new(test_squad(&manager, &home_players), away()).err(),
+        Some(error::FootsimError::ManagerAttribute {
+            manager_id: manager.id,
+            attribute: "tactical",
+        })
+    );
+    let mut home = test_squad(&home_manager, &home_players);
+    home.tactics.defense_line = 0;
+    assert_eq!(
+        game::Game::new(home, away()).err(),
+        Some(error::FootsimError::Tactic {
+            tactic: "defense_line"
+        })
+    );
+    // squads changed by hand after they were built
+    let mut home = test_squad(&home_manager, &home_players);
+    home.formation = formation::Formation::Custom([position::Position::Striker; 11]);
+    assert!(matches!(
+        game::Game::new(home, away()),
+        Err(error::FootsimError::Lineup(_))
+    ));
+    assert_eq!(
+        game::Game::new(
+            test_squad(&home_manager, &home_players),
+            test_squad(&home_manager, &home_players)
+        )
+        .err(),
+        Some(error::FootsimError::PlayerInBothSquads(home_players[0].id))
+    );
+
+    let mut game = game::Game::new(test_squad(&home_manager, &home_players), away()).unwrap();
+    assert_eq!(
+        game.set_condition(home_players[0].id, f32::NAN),
+        Err(error::FootsimError::Condition {
+            player_id: home_players[0].id
+        })
+    );
+    game.play().unwrap();
+    assert_eq!(game.play(), Err(error::FootsimError::AlreadyPlayed));
+
+    // the lowest and highest ratings and tactics play out
+    for (seed, rating) in (0..8).zip([1, u8::MAX].into_iter().cycle()) {
+        let mut players = home_players.clone();
+        for p in players.iter_mut() {
+            for attribute in [
+                &mut p.morale,
+                &mut p.form,
+                &mut p.tactical,
+                &mut p.leadership,
+                &mut p.decision_making,
+                &mut p.creativity,
+                &mut p.vision,
+                &mut p.fitness,
+                &mut p.pace,
+                &mut p.strength,
+                &mut p.stamina,
+                &mut p.jumping,
+                &mut p.passing,
+                &mut p.technique,
+                &mut p.heading,
+                &mut p.set_pieces,
+                &mut p.tackling,
+                &mut p.marking,
+                &mut p.goalkeeping,
+                &mut p.defensive_positioning,
+                &mut p.shooting,
+                &mut p.long_shots,
+                &mut p.attack_positioning,
+            ] {
+                *attribute = rating;
+            }
+        }
+        let mut home = test_squad(&home_manager, &players);
+        home.tactics = tactics::Tactics {
+            defense_line: rating,
+            compactness: rating,
+            aggression: rating,
+            build_up_speed: rating,
+            pass_range: rating,
+            ..test_tactics()
+        };
+        let mut game = game::Game::with_seed(home, away(), seed).unwrap();
+        game.set_rules(rules::MatchRules {
+            knockout: true,
+            ..Default::default()
+        });
+        game.play().unwrap();
+        assert!(game.get_winner().is_some());
+    }
+}
+
+#[test]
+fn leagues_play_a_double_round_robin() {
+    assert_eq!(
+        competition::League::new(vec![test_team(1)]).err(),
+        Some(error::FootsimError::NotEnoughTeams(1, 2))
+    );
+    assert_eq!(
+        competition::League::new(vec![test_team(1), test_team(2), test_team(1)]).err(),
+        Some(error::FootsimError::DuplicateTeam(1))
+    );
+
+    // an odd number of teams, one sits out each matchday
+    let ids = [1, 2, 3, 4, 5];
+    let mut league = competition::League::new(ids.map(test_team).to_vec()).unwrap();
+    league.set_seed(3);
+    let matchdays = league.get_matchdays();
+    assert_eq!(matchdays.len(), 10);
+    for matchday in matchdays {
+        assert_eq!(matchday.len(), 2);
+        let playing = matchday.iter().flat_map(|f| [f.home, f.away]);
+        assert_eq!(playing.clone().count(), ids.len() - 1);
+        assert!(ids
+            .iter()
+            .all(|id| playing.clone().filter(|p| p == id).count() <= 1));
+    }
+    for home in ids {
+        for away in ids.iter().filter(|&&away| away != home) {
+            let fixtures = matchdays.iter().flatten();
+            assert_eq!(
+                fixtures
+                    .filter(|f| f.home == home && f.away == *away)
+                    .count(),
+                1
+            );
+        }
+    }
+
+    let mut played = 0;
+    while let Some(table) = league.play_matchday().unwrap() {
+        played += 1;
+        assert_eq!(table.matchday, played);
+        let standings = &table.standings;
+        assert!(standings
+            .iter()
+            .enumerate()
+            .all(|(i, s)| s.position == i + 1));
+        assert!(standings.windows(2).all(|w| w[0].points >= w[1].points));
+        let sum = |value: fn(&_) -> u32| standings.iter().map(value).sum::<u32>();
+        assert_eq!(sum(|s| s.goals_for), sum(|s| s.goals_against));
+        assert_eq!(sum(|s| s.played), played as u32 * 4);
+        assert_eq!(sum(|s| s.won), sum(|s| s.lost));
+        assert_eq!(sum(|s| s.points), sum(|s| s.won) * 3 + sum(|s| s.drawn));
+        for s in standings {
+            assert_eq!(s.played, s.won + s.drawn + s.lost);
+            assert_eq!(
+                s.goal_difference,
+                s.goals_for as i32 - s.goals_against as i32
+            );
+        }
+    }
+    assert!(league.is_finished());
+    let table = league.get_table();
+    assert!(table.standings.iter().all(|s| s.played == 8));
+    assert!(league.play_matchday().unwrap().is_none());
+}
+
+#[test]
+fn tiebreakers_order_teams_level_on_points() {
+    let teams = [1, 2, 3, 4].map(test_team);
+    let result = |home, away, score| competition::Fixture {
+        home,
+        away,
+        score: Some(score),
+    };
+    // 1 and 2 are level on points, 1 won the match between them but 2 has the better goal
+    // difference and scored more
+    let fixtures = [
+        result(1, 2, (1, 0)),
+        result(1, 3, (0, 1)),
+        result(2, 3, (3, 0)),
+        result(1, 4, (0, 0)),
+        result(2, 4, (0, 0)),
+        competition::Fixture::new(3, 4),
+    ];
+    let top_two = |tiebreakers: &[competition::Tiebreaker]| {
+        let table = competition::Table::from_fixtures(1, &teams, fixtures.iter(), tiebreakers);
+        assert_eq!(table.get_standing(1).unwrap().points, 4);
+        assert_eq!(table.get_standing(2).unwrap().points, 4);
+        assert_eq!(table.get_standing(3).unwrap().played, 2);
+        [table.standings[0].team_id, table.standings[1].team_id]
+    };
+    use competition::Tiebreaker::*;
+    assert_eq!(top_two(&[GoalDifference, HeadToHead]), [2, 1]);
+    assert_eq!(top_two(&[GoalsScored]), [2, 1]);
+    assert_eq!(top_two(&[HeadToHead, GoalDifference]), [1, 2]);
+    // still level, in order of entry
+    assert_eq!(top_two(&[]), [1, 2]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn squads_and_results_round_trip_through_json() {
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let home = test_squad(&home_manager, &home_players);
+    let json = serde_json::to_string(&home).unwrap();
+    let loaded: squad::Squad = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    assert_eq!(loaded.subs.len(), home_players.len() - 11);
+
+    let mut game =
+        game::Game::with_seed(loaded.clone(), test_squad(&away_manager, &away_players), 7).unwrap();
+    game.play().unwrap();
+    let stats = game.get_home_stats();
+    let json = serde_json::to_string(&stats).unwrap();
+    assert_eq!(
+        serde_json::from_str::<game::GameStats>(&json).unwrap(),
+        stats
+    );
+    let events = serde_json::to_string(game.get_events()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<event::MatchEvent>>(&events).unwrap(),
+        game.get_events()
+    );
+
+    // the team name can be left out
+    let mut unnamed = serde_json::to_value(&loaded).unwrap();
+    unnamed.as_object_mut().unwrap().remove("name");
+    assert!(serde_json::from_value::<squad::Squad>(unnamed)
+        .unwrap()
+        .name
+        .is_empty());
+
+    // the starting eleven has to be complete
+    let mut short = serde_json::to_value(&loaded).unwrap();
+    short["players"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<squad::Squad>(short).is_err());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_parses_match_commands() {
+    use clap::{CommandFactory, Parser};
+
+    crate::cli::Cli::command().debug_assert();
+    let parse = |args: &[&str]| crate::cli::Cli::try_parse_from(args).is_ok();
+    assert!(parse(&[
+        "footsim", "play", "--home", "a.json", "--away", "b.json"
+    ]));
+    assert!(parse(&[
+        "footsim", "play", "--home", "a.json", "--away", "b.json", "--seed", "7", "--format",
+        "json",
+    ]));
+    assert!(parse(&[
+        "footsim",
+        "play",
+        "--home",
+        "a.json",
+        "--away",
+        "b.json",
+        "--commentary",
+        "excitable",
+    ]));
+    assert!(parse(&[
+        "footsim", "batch", "--home", "a.json", "--away", "b.json", "-n", "10",
+    ]));
+    assert!(!parse(&["footsim", "play", "--home", "a.json"]));
+    assert!(!parse(&[
+        "footsim", "play", "--home", "a.json", "--away", "b.json", "--format", "xml",
+    ]));
+}
+
+#[test]
+fn cups_play_a_bracket_down_to_a_winner() {
+    // 6 teams in a bracket of 8, the top two seeds get byes and can only meet in the final
+    let ids = [1, 2, 3, 4, 5, 6];
+    let mut cup = competition::Cup::new(ids.map(test_team).to_vec()).unwrap();
+    cup.set_seed(5);
+    assert_eq!(cup.get_round_count(), 3);
+    cup.draw();
+    let first = cup.get_rounds()[0].clone();
+    assert_eq!(first.get_name(), "quarter-finals");
+    let pairs = first
+        .ties
+        .iter()
+        .map(|t| (t.home, t.away))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        vec![(1, None), (4, Some(5)), (2, None), (3, Some(6))]
+    );
+
+    while let Some(round) = cup.play_round().unwrap() {
+        for tie in &round.ties {
+            let winner = tie.winner.unwrap();
+            assert!(winner == tie.home || Some(winner) == tie.away);
+            match tie.away {
+                None => assert!(tie.legs.is_empty()),
+                Some(_) => {
+                    assert_eq!(tie.legs.len(), 1);
+                    let (home, away) = tie.get_aggregate();
+                    match home.cmp(&away) {
+                        std::cmp::Ordering::Greater => assert_eq!(winner, tie.home),
+                        std::cmp::Ordering::Less => assert_eq!(Some(winner), tie.away),
+                        // level after extra time, decided on penalties
+                        std::cmp::Ordering::Equal => assert!(tie.shootout.is_some()),
+                    }
+                }
+            }
+        }
+    }
+    let rounds = cup.get_rounds();
+    assert_eq!(rounds.len(), 3);
+    assert_eq!(rounds[2].get_name(), "final");
+    // winners of neighbouring ties meet in the next round
+    for (round, next) in rounds.iter().zip(&rounds[1..]) {
+        for (i, tie) in next.ties.iter().enumerate() {
+            assert_eq!(Some(tie.home), round.ties[i * 2].winner);
+            assert_eq!(tie.away, round.ties[i * 2 + 1].winner);
+        }
+    }
+    assert_eq!(cup.get_winner(), rounds[2].ties[0].winner);
+    assert!(cup.play_round().unwrap().is_none());
+
+    // two legged ties, the final is a single match
+    let play_cup = |draw| {
+        let mut cup = competition::Cup::new(ids.map(test_team).to_vec()).unwrap();
+        cup.set_seed(8);
+        cup.set_draw(draw);
+        cup.set_rules(competition::CupRules {
+            two_legged: true,
+            away_goals: true,
+            ..Default::default()
+        });
+        cup.play().unwrap();
+        cup.get_rounds().to_vec()
+    };
+    let rounds = play_cup(competition::Draw::Random);
+    assert_eq!(rounds, play_cup(competition::Draw::Random));
+    for tie in rounds[..2].iter().flat_map(|r| &r.ties) {
+        if let Some(away) = tie.away {
+            assert_eq!(tie.legs.len(), 2);
+            assert_eq!((tie.legs[0].home, tie.legs[0].away), (tie.home, away));
+            assert_eq!((tie.legs[1].home, tie.legs[1].away), (away, tie.home));
+        }
+    }
+    assert_eq!(rounds[2].ties[0].legs.len(), 1);
+}
+
+#[test]
+fn tournaments_draw_groups_and_send_the_best_to_a_knockout() {
+    // teams 1, 4, 7 and 10 come from one country, every other team from its own
+    let team_from = |team_id: u32| {
+        let mut team = test_team(team_id);
+        let country_id = match team_id % 3 {
+            1 => 1,
+            _ => team_id + 100,
+        };
+        for player in team.players.iter_mut() {
+            std::sync::Arc::make_mut(player).country_id = country_id;
+        }
+        team
+    };
+    let ids = (1..=12).collect::<Vec<u32>>();
+    let new_tournament = |groups| {
+        let teams = ids.iter().map(|&id| team_from(id)).collect();
+        let mut tournament = competition::tournament::Tournament::new(teams, groups).unwrap();
+        tournament.set_seed(13);
+        tournament.set_separate_countries(true);
+        tournament
+    };
+    assert_eq!(team_from(4).get_country_id(), 1);
+
+    // 4 teams of a country can't be kept apart in 3 groups
+    assert_eq!(
+        new_tournament(3).draw(),
+        Err(error::FootsimError::NoValidDraw)
+    );
+
+    // 4 countries of 16 teams each fill every one of 16 groups with one team of each, one more
+    // team of each country is one too many, either way the draw is settled straight away
+    let crowded = |extra: u32| {
+        let teams = (1..=64 + extra)
+            .map(|team_id| {
+                let mut team = test_team(team_id);
+                for player in team.players.iter_mut() {
+                    std::sync::Arc::make_mut(player).country_id = team_id % 4;
+                }
+                team
+            })
+            .collect();
+        let mut tournament = competition::tournament::Tournament::new(teams, 16).unwrap();
+        tournament.set_separate_countries(true);
+        let start = std::time::Instant::now();
+        let draw = tournament.draw();
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+        draw.map(|_| tournament)
+    };
+    let tournament = crowded(0).unwrap();
+    for group in tournament.get_groups() {
+        let mut countries = group
+            .get_teams()
+            .iter()
+            .map(|t| t.get_country_id())
+            .collect::<Vec<_>>();
+        countries.sort();
+        assert_eq!(countries, vec![0, 1, 2, 3]);
+    }
+    assert_eq!(crowded(4).err(), Some(error::FootsimError::NoValidDraw));
+
+    let mut tournament = new_tournament(4);
+    tournament.set_best_placed(2);
+    tournament.draw().unwrap();
+    let groups = tournament.get_groups();
+    assert_eq!(groups.len(), 4);
+    for group in groups {
+        let teams = group.get_teams();
+        assert_eq!(teams.len(), 3);
+        // one team from each pot of seeds
+        let mut pots = teams.iter().map(|t| (t.id - 1) / 4).collect::<Vec<_>>();
+        pots.sort();
+        assert_eq!(pots, vec![0, 1, 2]);
+        assert!(teams.iter().filter(|t| t.get_country_id() == 1).count() <= 1);
+        // each team plays the others once
+        assert_eq!(group.get_matchdays().iter().flatten().count(), 3);
+    }
+
+    let report = tournament.play().unwrap();
+    assert_eq!(report.groups.len(), 4);
+    assert_eq!(report.groups[0].name, "group A");
+    assert_eq!(report.qualified.len(), 10);
+    // group winners are the top seeds of the knockout
+    for (group, &winner) in report.groups.iter().zip(&report.qualified) {
+        assert_eq!(group.table.standings[0].team_id, winner);
+        assert!(group.table.standings.iter().all(|s| s.played == 2));
+    }
+    // the best placed come from the third placed teams
+    let thirds = report
+        .groups
+        .iter()
+        .map(|g| g.table.standings[2].team_id)
+        .collect::<Vec<_>>();
+    assert!(report.qualified[8..].iter().all(|id| thirds.contains(id)));
+    // 10 teams in a bracket of 16
+    assert_eq!(report.knockout.len(), 4);
+    assert!(report.winner.is_some());
+    assert_eq!(
+        report.winner,
+        tournament.get_knockout().unwrap().get_winner()
+    );
+
+    let mut replay = new_tournament(4);
+    replay.set_best_placed(2);
+    assert_eq!(replay.play().unwrap(), report);
+}
+
+#[test]
+fn pyramids_move_teams_between_divisions() {
+    let division = |name: &str, ids: std::ops::RangeInclusive<u32>| {
+        competition::pyramid::Division::new(name, ids.map(test_team).collect())
+    };
+    let mut second = division("second", 5..=8);
+    // the winner goes up with the winner of a playoff between second and third
+    second.promoted = 1;
+    second.playoff_places = 2;
+    let mut third = division("third", 9..=12);
+    third.promoted = 1;
+    let divisions = vec![division("first", 1..=4), second, third];
+
+    // players keep the id of their team
+    let mut wrong = divisions.clone();
+    let player = std::sync::Arc::make_mut(&mut wrong[1].teams[0].players[3]);
+    player.team_id = 1;
+    let player_id = player.id;
+    assert_eq!(
+        competition::pyramid::Pyramid::new(wrong).err(),
+        Some(error::FootsimError::PlayerNotInTeam {
+            team_id: 5,
+            player_id
+        })
+    );
+    let mut crowded = divisions.clone();
+    crowded[1].playoff_places = 3;
+    assert_eq!(
+        competition::pyramid::Pyramid::new(crowded).err(),
+        Some(error::FootsimError::NotEnoughTeams(4, 5))
+    );
+
+    let mut pyramid = competition::pyramid::Pyramid::new(divisions).unwrap();
+    pyramid.set_seed(21);
+    for season in 1..=2 {
+        let before = pyramid
+            .get_divisions()
+            .iter()
+            .map(|d| d.teams.iter().map(|t| t.id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let report = pyramid.play_season().unwrap();
+        assert_eq!(report.season, season);
+        assert_eq!(pyramid.get_season(), season);
+        let (first, second, third) = (
+            &report.divisions[0],
+            &report.divisions[1],
+            &report.divisions[2],
+        );
+        assert!(first.promoted.is_empty() && third.relegated.is_empty());
+        assert_eq!(first.relegated.len(), 2);
+        assert_eq!(second.relegated.len(), 1);
+        assert_eq!(second.promoted[0], second.table.standings[0].team_id);
+        // the playoff is a final between second and third
+        assert_eq!(second.playoff.len(), 1);
+        let playoff = &second.playoff[0].ties[0];
+        assert_eq!(playoff.home, second.table.standings[1].team_id);
+        assert_eq!(playoff.away, Some(second.table.standings[2].team_id));
+        assert_eq!(second.promoted[1], playoff.winner.unwrap());
+        assert_eq!(third.promoted, vec![third.table.standings[0].team_id]);
+
+        for (d, division) in report.divisions.iter().enumerate() {
+            let ids = division
+                .table
+                .standings
+                .iter()
+                .map(|s| s.team_id)
+                .collect::<Vec<_>>();
+            let mut played = ids.clone();
+            played.sort();
+            let mut entered = before[d].clone();
+            entered.sort();
+            assert_eq!(played, entered);
+        }
+        let divisions = pyramid.get_divisions();
+        for id in &second.promoted {
+            assert!(divisions[0].get_team(*id).is_some());
+        }
+        for id in &first.relegated {
+            assert!(divisions[1].get_team(*id).is_some());
+        }
+        assert!(divisions[1].get_team(third.promoted[0]).is_some());
+        assert!(divisions[2].get_team(second.relegated[0]).is_some());
+        for division in divisions {
+            assert_eq!(division.teams.len(), 4);
+            for team in &division.teams {
+                assert_eq!(team.manager.team_id, team.id);
+                assert!(team.players.iter().all(|p| p.team_id == team.id));
+            }
+        }
+    }
+}
+
+#[test]
+fn shots_carry_their_xg() {
+    let home_players = test_players(1);
+    let mut tight_players = test_players(2);
+    for p in tight_players.iter_mut() {
+        p.tackling = 255;
+        p.marking = 255;
+        p.defensive_positioning = 255;
+    }
+    // (origin, xG) of every home shot over a few matches against the defenders
+    let shots = |away_players: &[player::Player]| {
+        let mut shots = Vec::new();
+        for seed in 0..10 {
+            let mut game = test_game(&home_players, away_players, seed);
+            game.play().unwrap();
+            let stats = game.get_home_stats();
+            let home_shots = game
+                .get_events()
+                .iter()
+                .filter(|e| e.side == Some(event::Side::Home))
+                .filter_map(|e| match e.kind {
+                    event::EventKind::Shot {
+                        player_id,
+                        origin,
+                        xg,
+                        ..
+                    } => Some((player_id, origin, xg)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let total = home_shots.iter().map(|s| s.2).sum::<f32>();
+            assert!((stats.xg - total).abs() < 1e-3);
+            let by_player = stats.xg_by_player.iter().map(|p| p.1).sum::<f32>();
+            assert!((by_player - total).abs() < 1e-3);
+            for (player_id, xg) in stats.xg_by_player.iter() {
+                let shooter = home_shots.iter().filter(|s| s.0 == *player_id);
+                assert!((shooter.map(|s| s.2).sum::<f32>() - xg).abs() < 1e-3);
+            }
+            assert!(home_shots.iter().all(|s| s.2 > 0.0 && s.2 < 1.0));
+            shots.extend(home_shots.into_iter().map(|(_, origin, xg)| (origin, xg)));
+        }
+        shots
+    };
+    let mean = |shots: &[(event::ShotOrigin, f32)], origin| {
+        let xg = shots
+            .iter()
+            .filter(|s| s.0 == origin)
+            .map(|s| s.1)
+            .collect::<Vec<_>>();
+        assert!(!xg.is_empty(), "no {:?} shots", origin);
+        xg.iter().sum::<f32>() / xg.len() as f32
+    };
+
+    let open = shots(&test_players(2));
+    // closer in is better, penalties best of all
+    assert!(mean(&open, event::ShotOrigin::Penalty) > mean(&open, event::ShotOrigin::OpenPlay));
+    assert!(mean(&open, event::ShotOrigin::OpenPlay) > mean(&open, event::ShotOrigin::LongShot));
+    // chances are harder to come by against a tight defence
+    let tight = shots(&tight_players);
+    assert!(mean(&tight, event::ShotOrigin::OpenPlay) < mean(&open, event::ShotOrigin::OpenPlay));
+}
+
+#[test]
+fn players_are_rated_out_of_ten() {
+    use event::EventKind::*;
+    let at = |minute, side, kind| event::MatchEvent {
+        minute,
+        side: Some(side),
+        kind,
+    };
+    let (home, away) = (event::Side::Home, event::Side::Away);
+    let events = vec![
+        at(
+            10,
+            home,
+            Shot {
+                player_id: 10,
+                assist_id: Some(7),
+                on_target: true,
+                origin: event::ShotOrigin::OpenPlay,
+                xg: 0.2,
+            },
+        ),
+        at(
+            10,
+            home,
+            Goal {
+                player_id: 10,
+                assist_id: Some(7),
+                goal_type: event::GoalType::OpenPlay,
+            },
+        ),
+        at(
+            30,
+            away,
+            Foul {
+                player_id: 22,
+                fouled_id: 10,
+            },
+        ),
+        at(30, home, Penalty { player_id: 10 }),
+        at(
+            30,
+            home,
+            Shot {
+                player_id: 10,
+                assist_id: None,
+                on_target: true,
+                origin: event::ShotOrigin::Penalty,
+                xg: 0.76,
+            },
+        ),
+        at(
+            30,
+            away,
+            Save {
+                keeper_id: 21,
+                shooter_id: 10,
+            },
+        ),
+        at(
+            50,
+            away,
+            Tackle {
+                player_id: 23,
+                tackled_id: 7,
+            },
+        ),
+        at(
+            60,
+            home,
+            Foul {
+                player_id: 3,
+                fouled_id: 24,
+            },
+        ),
+        at(
+            60,
+            home,
+            Card {
+                player_id: 3,
+                card: event::Card::Red,
+                reason: event::CardReason::SeriousFoulPlay,
+            },
+        ),
+    ];
+    let players = |ids: [u32; 4]| {
+        ids.into_iter()
+            .zip([
+                position::Position::Goalkeeper,
+                position::Position::CenterBack,
+                position::Position::CenterMidfield,
+                position::Position::Striker,
+            ])
+            .collect::<Vec<_>>()
+    };
+    let ratings = rating::MatchRatings::from_events(
+        &players([1, 3, 7, 10]),
+        &players([21, 22, 23, 24]),
+        &events,
+    );
+    let rating = |id| ratings.get_rating(id).unwrap();
+    assert_eq!(ratings.ratings.len(), 8);
+    assert_eq!(ratings.man_of_the_match, Some(10));
+    // the scorer, then the assister, then the rest of the winning team
+    assert!(rating(10) > rating(7));
+    assert!(rating(7) > rating(24));
+    // a clean sheet for the keeper, a red card outweighs it
+    assert!(rating(1) > rating(3));
+    // a save and a tackle, against giving away a penalty
+    assert!(rating(21) > rating(22));
+    assert!(rating(23) > rating(24));
+    assert!(rating(22) < rating(24));
+    // a defender brought on at the end gets next to nothing for the clean sheet
+    let late = [
+        at(
+            89,
+            away,
+            Substitution {
+                player_off: 22,
+                player_on: 25,
+                reason: event::SubReason::Scoreline,
+            },
+        ),
+        event::MatchEvent {
+            minute: 90,
+            side: None,
+            kind: FullTime,
+        },
+    ];
+    let ratings = rating::MatchRatings::from_events(
+        &players([1, 3, 7, 10]),
+        &[
+            (21, position::Position::Goalkeeper),
+            (22, position::Position::CenterBack),
+            (25, position::Position::CenterBack),
+        ],
+        &late,
+    );
+    assert_eq!(ratings.get_rating(22), Some(6.5));
+    assert_eq!(ratings.get_rating(25), Some(6.0));
+
+    // everyone who took the pitch is rated once
+    let (home_manager, away_manager) = (test_manager(1, 1), test_manager(2, 2));
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let game = play_seeded(4);
+    let ratings = game.get_ratings();
+    for side in [event::Side::Home, event::Side::Away] {
+        let (stats, squad) = match side {
+            event::Side::Home => (
+                game.get_home_stats(),
+                test_squad(&home_manager, &home_players),
+            ),
+            event::Side::Away => (
+                game.get_away_stats(),
+                test_squad(&away_manager, &away_players),
+            ),
+        };
+        // starters are rated in the position they kicked off in
+        let starters = squad
+            .players
+            .iter()
+            .map(|p| p.id)
+            .zip(squad.formation.slots())
+            .collect::<Vec<_>>();
+        assert_eq!(game.get_participants(side)[..11], starters[..]);
+        let rated = ratings
+            .ratings
+            .iter()
+            .filter(|r| r.side == side)
+            .map(|r| r.player_id)
+            .collect::<Vec<_>>();
+        assert_eq!(rated.len(), 11 + stats.substitutions.len());
+        assert!(stats
+            .substitutions
+            .iter()
+            .all(|(off, on)| { rated.contains(off) && rated.contains(on) }));
+    }
+    assert!(ratings
+        .ratings
+        .iter()
+        .all(|r| (1.0..=10.0).contains(&r.rating)));
+    let best = ratings.ratings.iter().map(|r| r.rating).fold(0.0, f32::max);
+    assert_eq!(
+        ratings.get_rating(ratings.man_of_the_match.unwrap()),
+        Some(best)
+    );
+}
+
+#[test]
+fn player_stats_add_up_to_the_team_totals() {
+    for seed in 0..10 {
+        let game = play_seeded(seed);
+        let (home, away) = (game.get_home_stats(), game.get_away_stats());
+        for (stats, opp_stats) in [(&home, &away), (&away, &home)] {
+            let sum =
+                |f: fn(&game::PlayerMatchStats) -> u32| stats.players.iter().map(f).sum::<u32>();
+            assert_eq!(sum(|p| p.shots as u32), stats.shots as u32);
+            assert_eq!(
+                sum(|p| p.shots_on_target as u32),
+                stats.shots_on_target as u32
+            );
+            assert_eq!(
+                sum(|p| p.goals as u32) + stats.own_goals.len() as u32,
+                stats.goals as u32
+            );
+            assert_eq!(sum(|p| p.assists as u32), stats.assists.len() as u32);
+            assert_eq!(sum(|p| p.passes as u32), stats.passes as u32);
+            assert_eq!(
+                sum(|p| p.passes_completed as u32),
+                stats.passes_completed as u32
+            );
+            assert_eq!(sum(|p| p.crosses as u32), stats.crosses as u32);
+            assert_eq!(sum(|p| p.tackles as u32), stats.tackles as u32);
+            assert_eq!(sum(|p| p.interceptions as u32), stats.interceptions as u32);
+            assert_eq!(sum(|p| p.fouls as u32), stats.fouls as u32);
+            assert_eq!(sum(|p| p.fouled as u32), opp_stats.fouls as u32);
+            assert_eq!(sum(|p| p.saves as u32), stats.saves as u32);
+            assert_eq!(
+                sum(|p| p.yellow_cards as u32),
+                stats.yellow_cards.len() as u32
+            );
+            assert_eq!(sum(|p| p.red_cards as u32), stats.red_cards.len() as u32);
+            let xg = stats.players.iter().map(|p| p.xg).sum::<f32>();
+            assert!((xg - stats.xg).abs() < 1e-3);
+            let distance = stats.players.iter().map(|p| p.distance).sum::<f32>();
+            assert!((distance - stats.distance).abs() < 1e-2);
+            // the halves add up to the match
+            let passes = stats.halves.iter().map(|h| h.passes as u32).sum::<u32>();
+            assert_eq!(passes, stats.passes as u32);
+
+            // the eleven that started, then every sub who came on
+            assert_eq!(stats.players.len(), 11 + stats.substitutions.len());
+            assert!(stats.passes > 300 && stats.passes_completed < stats.passes);
+            for p in stats.players.iter() {
+                assert!(p.minutes <= 90);
+                let involved = stats
+                    .substitutions
+                    .iter()
+                    .any(|s| s.0 == p.player_id || s.1 == p.player_id)
+                    || stats.red_cards.contains(&p.player_id)
+                    || stats.injuries.iter().any(|i| i.player_id == p.player_id);
+                if !involved {
+                    assert_eq!(p.minutes, 90);
+                    assert!(p.distance > 4.0 && p.distance < 14.0);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn commentary_summary_names_the_winner_of_the_tie() {
+    // second legs where the home team went in a goal down
+    let (mut shootouts, mut aggregates) = (0, 0);
+    for seed in 0..60 {
+        let mut game = test_game(&test_players(1), &test_players(2), seed);
+        game.set_rules(rules::MatchRules {
+            knockout: true,
+            extra_time: true,
+            away_goals: false,
+            first_leg: Some((0, 1)),
+        });
+        game.play().unwrap();
+        let (home, away) = (game.get_home_stats(), game.get_away_stats());
+        let mut commentator = commentary::Commentator::new(commentary::TemplateSet::english(
+            commentary::Tone::Neutral,
+        ));
+        commentator.set_team_names("Rovers", "United");
+        let summary = commentator.commentate(&game).summary;
+        let winner = match game.get_winner().unwrap() {
+            event::Side::Home => "Rovers",
+            event::Side::Away => "United",
+        };
+        assert!(summary.starts_with(winner));
+        assert!(!summary.contains('{'));
+        match game.get_shootout_score() {
+            // level on aggregate after winning this leg by the goal
+            Some(_) => {
+                assert_eq!(home.goals, away.goals + 1);
+                let (level, _) = game.get_aggregate_score().unwrap();
+                assert!(summary.contains(&format!("after a {}-{} draw", level, level)));
+                shootouts += 1;
+            }
+            // through on aggregate after drawing this leg
+            None if home.goals == away.goals => {
+                assert_eq!(winner, "United");
+                let (home_total, away_total) = game.get_aggregate_score().unwrap();
+                assert!(summary.contains(&format!("{}-{} on aggregate", away_total, home_total)));
+                aggregates += 1;
+            }
+            // whoever won this leg won the tie
+            None => assert_eq!(winner == "Rovers", home.goals > away.goals),
+        }
+    }
+    assert!(shootouts > 0 && aggregates > 0);
+}
+
+#[test]
+fn commentary_names_the_players_in_every_line() {
+    let name = |p: &mut player::Player| p.name = format!("Player{}", p.id);
+    let mut home_players = test_players(1);
+    let mut away_players = test_players(2);
+    home_players
+        .iter_mut()
+        .chain(away_players.iter_mut())
+        .for_each(name);
+    let mut game = test_game(&home_players, &away_players, 4);
+    game.play().unwrap();
+    let (home, away) = (game.get_home_stats(), game.get_away_stats());
+
+    for tone in [commentary::Tone::Neutral, commentary::Tone::Excitable] {
+        let mut commentator = commentary::Commentator::new(commentary::TemplateSet::english(tone));
+        commentator.set_team_names("Rovers", "United");
+        let commentary = commentator.commentate(&game);
+        assert!(commentary.lines.iter().all(|l| !l.text.contains('{')));
+        assert!(!commentary.summary.contains('{'));
+        // a line for every goal, naming the scorer
+        let goals = game
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.kind {
+                event::EventKind::Goal { player_id, .. } => Some((e.minute, player_id)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let goal_lines = commentary
+            .lines
+            .iter()
+            .filter(|l| {
+                matches!(
+                    l.moment,
+                    commentary::Moment::Goal
+                        | commentary::Moment::AssistedGoal
+                        | commentary::Moment::HeadedGoal
+                        | commentary::Moment::LongShotGoal
+                        | commentary::Moment::PenaltyGoal
+                        | commentary::Moment::FreeKickGoal
+                        | commentary::Moment::OwnGoal
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(goal_lines.len(), goals.len());
+        for (line, (minute, scorer)) in goal_lines.iter().zip(goals) {
+            assert_eq!(line.minute, minute);
+            assert!(line.text.contains(&format!("Player{}", scorer)));
+            assert!(line.to_string().starts_with(&format!("{}' ", minute)));
+        }
+        // players taken off are still named
+        for e in game.get_events() {
+            if let event::EventKind::Substitution {
+                player_off,
+                player_on,
+                ..
+            } = e.kind
+            {
+                let (off, on) = (
+                    format!("Player{}", player_off),
+                    format!("Player{}", player_on),
+                );
+                assert!(commentary.lines.iter().any(|l| {
+                    l.moment == commentary::Moment::Substitution
+                        && l.minute == e.minute
+                        && l.text.contains(&off)
+                        && l.text.contains(&on)
+                }));
+            }
+        }
+        assert_eq!(commentary.lines[0].moment, commentary::Moment::KickOff);
+        assert!(commentary.lines[0].text.contains("Rovers"));
+        assert!(commentary.summary.contains("Rovers") && commentary.summary.contains("United"));
+        let score = match home.goals.cmp(&away.goals) {
+            std::cmp::Ordering::Less => format!("{}-{}", away.goals, home.goals),
+            _ => format!("{}-{}", home.goals, away.goals),
+        };
+        assert!(commentary.summary.contains(&score));
+    }
+
+    // a set only speaks for the moments it has templates for
+    let mut templates = commentary::TemplateSet::new("en", commentary::Tone::Neutral, "and");
+    templates.set_templates(commentary::Moment::FullTime, &["FT {score}"]);
+    let commentary = commentary::Commentator::new(templates).commentate(&game);
+    assert_eq!(commentary.lines.len(), 1);
+    assert_eq!(
+        commentary.lines[0].text,
+        format!("FT {}-{}", home.goals, away.goals)
+    );
+    assert!(commentary.summary.is_empty());
+}
+
+#[test]
+fn tactics_stay_as_set_without_a_manager_ai() {
+    for seed in 0..10 {
+        let mut game = play_seeded(seed);
+        assert!(!game
+            .get_events()
+            .iter()
+            .any(|e| matches!(e.kind, event::EventKind::TacticsChange { .. })));
+        for side in [event::Side::Home, event::Side::Away] {
+            assert_eq!(*game.get_tactics(side), test_tactics());
+        }
+
+        // the tactics left at full time are the last change made
+        game = test_game(&test_players(1), &test_players(2), seed);
+        game.set_manager_ai(manager_ai::ManagerAI::default());
+        game.play().unwrap();
+        for side in [event::Side::Home, event::Side::Away] {
+            let last = game
+                .get_events()
+                .iter()
+                .rev()
+                .filter(|e| e.side == Some(side))
+                .filter_map(|e| match &e.kind {
+                    event::EventKind::TacticsChange { tactics } => Some(tactics.clone()),
+                    _ => None,
+                })
+                .next()
+                .unwrap_or_else(test_tactics);
+            assert_eq!(*game.get_tactics(side), last);
+        }
+    }
+}
+
+#[test]
+fn managers_change_tactics_to_suit_the_match() {
+    let ai = manager_ai::ManagerAI::default();
+    let plan = test_tactics();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut best = test_manager(1, 1);
+    best.tactical = 255;
+    let mut worst = test_manager(2, 2);
+    worst.tactical = 0;
+    let situation = |minute, goal_difference, players, condition| manager_ai::Situation {
+        minute,
+        length: 90,
+        goal_difference,
+        players,
+        opp_players: 11,
+        condition,
+    };
+
+    // nothing to react to early on in a level game
+    assert_eq!(
+        ai.adjust(&best, &plan, &situation(10, 0, 11, 100.0), &mut rng),
+        plan
+    );
+    // chasing the game late on
+    let chasing = ai.adjust(&best, &plan, &situation(85, -1, 11, 100.0), &mut rng);
+    assert!(chasing.defense_line > plan.defense_line);
+    assert!(chasing.build_up_speed > plan.build_up_speed);
+    assert!(chasing.shoot_more_often && chasing.cross_more_often);
+    // protecting a lead late on
+    let leading = ai.adjust(&best, &plan, &situation(85, 1, 11, 100.0), &mut rng);
+    assert!(leading.defense_line < plan.defense_line);
+    assert_eq!(leading.attack_width, tactics::Width::Central);
+    // a man down, or a tired team, drops deeper
+    let down = ai.adjust(&best, &plan, &situation(30, 0, 10, 100.0), &mut rng);
+    assert!(down.defense_line < plan.defense_line);
+    let tired = ai.adjust(&best, &plan, &situation(30, 0, 11, 50.0), &mut rng);
+    assert!(tired.defense_line < plan.defense_line && tired.build_up_speed < plan.build_up_speed);
+
+    // better tacticians look more often and go further
+    assert!(ai.get_interval(&best) < ai.get_interval(&worst));
+    let push = |manager: &manager::Manager, rng: &mut StdRng| {
+        (0..200)
+            .map(|_| ai.adjust(manager, &plan, &situation(85, -1, 11, 100.0), rng))
+            .map(|t| t.defense_line as f32 - plan.defense_line as f32)
+            .sum::<f32>()
+    };
+    assert!(push(&best, &mut rng) > push(&worst, &mut rng));
+
+    // changes go on the timeline and into play, none with the manager AI turned off
+    let (home_players, away_players) = (test_players(1), test_players(2));
+    let mut changes = 0;
+    for seed in 0..10 {
+        for enabled in [true, false] {
+            let mut game = test_game(&home_players, &away_players, seed);
+            // off unless set
+            if enabled {
+                game.set_manager_ai(manager_ai::ManagerAI::default());
+            }
+            game.play().unwrap();
+            for side in [event::Side::Home, event::Side::Away] {
+                let mut current = plan.clone();
+                for e in game.get_events().iter().filter(|e| e.side == Some(side)) {
+                    if let event::EventKind::TacticsChange { tactics } = &e.kind {
+                        assert!(enabled);
+                        assert_ne!(*tactics, current);
+                        assert!(tactics.validate().is_ok());
+                        current = tactics.clone();
+                        changes += 1;
+                    }
+                }
+            }
+        }
+    }
+    assert!(changes > 0);
+}